@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::solution::Part;
+
+/// Expected results for a day, read from `resources/dN/answers.toml`:
+///
+/// ```toml
+/// [example]
+/// part1 = 4361
+/// part2 = 467835
+///
+/// [input]
+/// part1 = 539590
+/// ```
+///
+/// Only this flat subset of TOML is understood: `[section]` headers, `key = value` pairs with
+/// bare or double-quoted values, and `#` comments.
+pub struct Answers {
+    sections: HashMap<String, HashMap<String, String>>,
+}
+
+pub fn answers_path(day: u8) -> PathBuf {
+    return Path::new("resources")
+        .join(format!("d{}", day))
+        .join("answers.toml");
+}
+
+fn part_key(part: Part) -> &'static str {
+    return match part {
+        Part::One => "part1",
+        Part::Two => "part2",
+    };
+}
+
+impl Answers {
+    pub fn parse(s: &str) -> Result<Answers> {
+        let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut section: Option<String> = None;
+
+        for (line_num, line) in s.lines().enumerate() {
+            let line = line.split_once('#').map_or(line, |(code, _)| code).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(name.trim().to_string());
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(Error::parse(line_num + 1, "expected `key = value`"))?;
+            let section = section
+                .as_ref()
+                .ok_or(Error::parse(line_num + 1, "value outside of a [section]"))?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.to_string());
+        }
+        return Ok(Answers { sections });
+    }
+
+    // a day without an answers file simply has nothing to verify
+    pub fn load(day: u8) -> Result<Option<Answers>> {
+        let path = answers_path(day);
+        return match fs::read_to_string(&path) {
+            Ok(s) => Answers::parse(&s)
+                .map(Some)
+                .map_err(|err| Error::InFile(path, Box::new(err))),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::Io(path.display().to_string(), err)),
+        };
+    }
+
+    /// `section` is either "example" or "input".
+    pub fn expected(&self, section: &str, part: Part) -> Option<&str> {
+        return self
+            .sections
+            .get(section)?
+            .get(part_key(part))
+            .map(|s| s.as_str());
+    }
+}
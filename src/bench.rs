@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::json;
+use crate::registry::Day;
+use crate::runner::{self, Source};
+use crate::solution::Part;
+
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn of(mut samples: Vec<Duration>) -> Stats {
+        samples.sort();
+        return Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        };
+    }
+
+    fn to_json(&self) -> String {
+        return format!(
+            "{{\"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.max.as_nanos()
+        );
+    }
+}
+
+pub struct PartBench {
+    pub part: Part,
+    pub answer: String,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+pub struct Bench {
+    pub day: u8,
+    pub source: String,
+    pub runs: usize,
+    pub parts: Vec<PartBench>,
+}
+
+// solve each part `runs` times on the same input, timing parsing and solving separately
+pub fn bench_day(day: &Day, parts: &[Part], source: &Source, runs: usize) -> Result<Bench> {
+    let loaded = runner::read_source(source)?;
+    let mut results = vec![];
+    for &part in parts {
+        let input = loaded.as_deref().unwrap_or(day.example(part));
+        let mut answer = String::new();
+        let mut parse_samples = vec![];
+        let mut solve_samples = vec![];
+        for _ in 0..runs.max(1) {
+            let (res, timing) = runner::run(day, part, input)?;
+            answer = res;
+            parse_samples.push(timing.parse);
+            solve_samples.push(timing.solve);
+        }
+        results.push(PartBench {
+            part,
+            answer,
+            parse: Stats::of(parse_samples),
+            solve: Stats::of(solve_samples),
+        });
+    }
+    return Ok(Bench {
+        day: day.number,
+        source: source.to_string(),
+        runs: runs.max(1),
+        parts: results,
+    });
+}
+
+pub fn print_bench(bench: &Bench) {
+    println!("Day {} ({}), {} runs", bench.day, bench.source, bench.runs);
+    println!(
+        "{:>4}  {:<5}  {:>10}  {:>10}  {:>10}",
+        "Part", "Phase", "min", "median", "max"
+    );
+    for part in &bench.parts {
+        for (phase, stats) in [("parse", &part.parse), ("solve", &part.solve)] {
+            println!(
+                "{:>4}  {:<5}  {:>10.2?}  {:>10.2?}  {:>10.2?}",
+                part.part, phase, stats.min, stats.median, stats.max
+            );
+        }
+    }
+}
+
+// short hash of the checked out commit, so saved results can be compared across commits
+fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    return Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+}
+
+pub fn to_json(bench: &Bench) -> String {
+    let commit = current_commit().map_or("null".to_string(), |c| json::quote(&c));
+    let parts: Vec<String> = bench
+        .parts
+        .iter()
+        .map(|part| {
+            format!(
+                "    {{\"part\": {}, \"answer\": {}, \"parse\": {}, \"solve\": {}}}",
+                part.part,
+                json::quote(&part.answer),
+                part.parse.to_json(),
+                part.solve.to_json()
+            )
+        })
+        .collect();
+    return format!(
+        "{{\n  \"day\": {},\n  \"source\": {},\n  \"runs\": {},\n  \"commit\": {},\n  \"parts\": [\n{}\n  ]\n}}\n",
+        bench.day,
+        json::quote(&bench.source),
+        bench.runs,
+        commit,
+        parts.join(",\n")
+    );
+}
+
+pub fn write_json(bench: &Bench, path: &Path) -> Result<()> {
+    return fs::write(path, to_json(bench))
+        .map_err(|err| Error::Io(path.display().to_string(), err));
+}
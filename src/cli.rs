@@ -0,0 +1,530 @@
+use std::path::PathBuf;
+use std::slice::Iter;
+
+use advent_of_code_2023::d3::query::{Arity, GearQuery, Reducer};
+use advent_of_code_2023::d4::generate::DeckSpec;
+use advent_of_code_2023::d4::{Overrun, ParseMode};
+use advent_of_code_2023::d5;
+use advent_of_code_2023::interval::Interval;
+use advent_of_code_2023::log::Level;
+use advent_of_code_2023::registry::{self, Day};
+use advent_of_code_2023::runner::{input_path, Source};
+use advent_of_code_2023::solution::Part;
+
+pub const USAGE: &str = "Usage:
+  advent_of_code_2023 run <day> [--part 1|2] [--example | --input <path> | --stdin]
+  advent_of_code_2023 run --all [--part 1|2] [--example]
+  advent_of_code_2023 verify [<day> | --all]
+  advent_of_code_2023 bench <day> [--part 1|2] [--runs N] [--example | --input <path> | --stdin]
+                      [--json <path>]
+  advent_of_code_2023 schematic report [--json] [--example | --input <path> | --stdin]
+  advent_of_code_2023 schematic gears [--symbols <chars>] [--exactly N | --at-least N]
+                      [--reduce product|sum|max] [--example | --input <path> | --stdin]
+  advent_of_code_2023 schematic render [--svg <path>] [--example | --input <path> | --stdin]
+  advent_of_code_2023 scratchcards explain [--overrun clamp|error] [--strict]
+                      [--example | --input <path> | --stdin]
+  advent_of_code_2023 scratchcards check [--example | --input <path> | --stdin]
+  advent_of_code_2023 scratchcards generate [--cards N] [--winning N] [--own N]
+                      [--win-probability P] [--seed N] [--output <path>]
+  advent_of_code_2023 almanac mapping [--from <category>] [--to <category>]
+                      [--example | --input <path> | --stdin]
+  advent_of_code_2023 almanac lookup <number> [--from <category>] [--to <category>]
+                      [--example | --input <path> | --stdin]
+  advent_of_code_2023 almanac seeds <location | start..end | --below N>
+                      [--example | --input <path> | --stdin]
+  advent_of_code_2023 almanac check [--example | --input <path> | --stdin]
+  advent_of_code_2023 list
+  advent_of_code_2023 <day>            same as `run <day>`
+
+Without a source flag both the example and resources/dN/input.txt are run.
+`verify` checks both against resources/dN/answers.toml, every day by default.
+`bench` defaults to 10 runs on resources/dN/input.txt and reports min/median/max timings.
+`schematic` inspects day 3, by default on resources/d3/input.txt; `gears` defaults to the
+puzzle's query: '*' touching exactly two numbers, multiplied. `render` colors part numbers,
+rejected numbers and gears in the terminal, or writes them to an SVG file.
+`scratchcards explain` lists, for every day 4 card, its matches, points and the copies won;
+`--overrun` chooses between ignoring and reporting wins past the last card. `check` and
+`--strict` reject malformed cards instead of skipping what does not parse. `generate` writes
+a random deck shaped like the puzzle input, the same for the same seed, to stdout by default.
+`almanac mapping` prints the day 5 maps from one category to another, seed to location by
+default, composed into a single mapping, and `lookup` applies it to a number; `almanac seeds`
+walks the chain backwards, listing every seed range that lands on the locations; `check`
+prints the chain of categories and the source ranges that overlap inside a map.
+Any command also takes -v, -vv or -vvv, or --log error|warn|info|debug|trace, to report what
+it does on stderr, warnings only by default; -vvv traces day 5's pieces through every map.";
+
+pub enum SchematicAction {
+    Report { json: bool },
+    Gears(GearQuery),
+    Render { svg: Option<PathBuf> },
+}
+
+pub enum ScratchcardsAction {
+    Explain { overrun: Overrun },
+    Check,
+}
+
+pub enum AlmanacAction {
+    Mapping {
+        from: String,
+        to: String,
+    },
+    Lookup {
+        number: i64,
+        from: String,
+        to: String,
+    },
+    Seeds(Interval),
+    Check,
+}
+
+pub enum Command {
+    Run {
+        days: Vec<Day>,
+        parts: Vec<Part>,
+        source: Option<Source>,
+    },
+    Verify {
+        days: Vec<Day>,
+    },
+    Schematic {
+        action: SchematicAction,
+        source: Source,
+    },
+    Scratchcards {
+        action: ScratchcardsAction,
+        mode: ParseMode,
+        source: Source,
+    },
+    Almanac {
+        action: AlmanacAction,
+        source: Source,
+    },
+    GenerateDeck {
+        spec: DeckSpec,
+        output: Option<PathBuf>,
+    },
+    Bench {
+        day: Day,
+        parts: Vec<Part>,
+        source: Source,
+        runs: usize,
+        json: Option<PathBuf>,
+    },
+    List,
+    Help,
+}
+
+fn parse_day(s: &str) -> Result<Day, String> {
+    let number: u8 = s
+        .parse()
+        .map_err(|_| format!("invalid day '{}', expected a number", s))?;
+    return registry::find(number).ok_or_else(|| {
+        let known: Vec<String> = registry::days()
+            .iter()
+            .map(|day| day.number.to_string())
+            .collect();
+        format!(
+            "day {} is not implemented, available days: {}",
+            number,
+            known.join(", ")
+        )
+    });
+}
+
+fn parse_part(s: Option<&String>) -> Result<Part, String> {
+    return match s.map(|s| s.as_str()) {
+        Some("1") => Ok(Part::One),
+        Some("2") => Ok(Part::Two),
+        Some(other) => Err(format!("invalid part '{}', expected 1 or 2", other)),
+        None => Err("--part needs a value, 1 or 2".to_string()),
+    };
+}
+
+fn parse_count(flag: &str, s: Option<&String>) -> Result<usize, String> {
+    return s
+        .and_then(|s| s.parse().ok())
+        .ok_or(format!("{} needs a number", flag));
+}
+
+fn set_source(source: &mut Option<Source>, new_source: Source) -> Result<(), String> {
+    if source.is_some() {
+        return Err("--example, --input and --stdin are mutually exclusive".to_string());
+    }
+    *source = Some(new_source);
+    return Ok(());
+}
+
+// consume --example, --input <path> or --stdin, returns false for any other argument
+fn parse_source_flag(
+    arg: &str,
+    args: &mut Iter<String>,
+    source: &mut Option<Source>,
+) -> Result<bool, String> {
+    let new_source = match arg {
+        "--example" => Source::Example,
+        "--stdin" => Source::Stdin,
+        "--input" | "-i" => Source::File(PathBuf::from(args.next().ok_or("--input needs a path")?)),
+        _ => return Ok(false),
+    };
+    set_source(source, new_source)?;
+    return Ok(true);
+}
+
+// -v, -vv, -vvv and so on
+fn is_verbosity_flag(arg: &str) -> bool {
+    return arg
+        .strip_prefix('-')
+        .is_some_and(|vs| !vs.is_empty() && vs.bytes().all(|b| b == b'v'));
+}
+
+// consume -v, -vv, ... or --log <level>, returns false for any other argument; only called
+// where a flag is expected, so the value of --input or --json is never taken for one
+fn parse_log_flag(arg: &str, args: &mut Iter<String>, level: &mut Level) -> Result<bool, String> {
+    match arg {
+        "--log" => *level = args.next().ok_or("--log needs a level")?.parse()?,
+        flag if is_verbosity_flag(flag) => *level = level.louder(flag.len() - 1),
+        _ => return Ok(false),
+    }
+    return Ok(true);
+}
+
+// a number, negative ones included, rather than a flag
+fn is_number(arg: &str) -> bool {
+    return arg
+        .strip_prefix('-')
+        .unwrap_or(arg)
+        .starts_with(|c: char| c.is_ascii_digit());
+}
+
+fn parse_run(args: &[String], level: &mut Level) -> Result<Command, String> {
+    let mut days: Option<Vec<Day>> = None;
+    let mut parts = vec![Part::One, Part::Two];
+    let mut source: Option<Source> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if parse_log_flag(arg, &mut args, level)? || parse_source_flag(arg, &mut args, &mut source)?
+        {
+            continue;
+        }
+        match arg.as_str() {
+            "--all" => days = Some(registry::days()),
+            "--part" | "-p" => parts = vec![parse_part(args.next())?],
+            flag if flag.starts_with('-') => return Err(format!("unknown flag '{}'", flag)),
+            day if days.is_none() => days = Some(vec![parse_day(day)?]),
+            extra => return Err(format!("unexpected argument '{}'", extra)),
+        }
+    }
+
+    let days = days.ok_or("run needs a day or --all")?;
+    if days.len() > 1 && matches!(source, Some(Source::File(_)) | Some(Source::Stdin)) {
+        return Err("--input and --stdin need a single day, not --all".to_string());
+    }
+    return Ok(Command::Run {
+        days,
+        parts,
+        source,
+    });
+}
+
+fn parse_bench(args: &[String], level: &mut Level) -> Result<Command, String> {
+    let mut day: Option<Day> = None;
+    let mut parts = vec![Part::One, Part::Two];
+    let mut source: Option<Source> = None;
+    let mut runs = 10;
+    let mut json: Option<PathBuf> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if parse_log_flag(arg, &mut args, level)? || parse_source_flag(arg, &mut args, &mut source)?
+        {
+            continue;
+        }
+        match arg.as_str() {
+            "--part" | "-p" => parts = vec![parse_part(args.next())?],
+            "--runs" | "-n" => {
+                let n = args.next().ok_or("--runs needs a number")?;
+                runs = n
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(format!("invalid run count '{}'", n))?;
+            }
+            "--json" => json = Some(PathBuf::from(args.next().ok_or("--json needs a path")?)),
+            flag if flag.starts_with('-') => return Err(format!("unknown flag '{}'", flag)),
+            d if day.is_none() => day = Some(parse_day(d)?),
+            extra => return Err(format!("unexpected argument '{}'", extra)),
+        }
+    }
+
+    let day = day.ok_or("bench needs a day")?;
+    let source = source.unwrap_or(Source::File(input_path(day.number)));
+    return Ok(Command::Bench {
+        day,
+        parts,
+        source,
+        runs,
+        json,
+    });
+}
+
+fn parse_schematic(args: &[String], level: &mut Level) -> Result<Command, String> {
+    let (action_s, args) = args
+        .split_first()
+        .ok_or("schematic needs an action: report, gears or render")?;
+    let mut source: Option<Source> = None;
+    let mut json = false;
+    let mut svg: Option<PathBuf> = None;
+    let mut query = GearQuery::gear_ratios();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if parse_log_flag(arg, &mut args, level)? || parse_source_flag(arg, &mut args, &mut source)?
+        {
+            continue;
+        }
+        match arg.as_str() {
+            "--json" => json = true,
+            "--svg" => svg = Some(PathBuf::from(args.next().ok_or("--svg needs a path")?)),
+            "--symbols" => {
+                let symbols = args.next().ok_or("--symbols needs the symbol characters")?;
+                query.symbols = symbols.chars().collect();
+            }
+            "--exactly" => query.arity = Arity::Exactly(parse_count(arg, args.next())?),
+            "--at-least" => query.arity = Arity::AtLeast(parse_count(arg, args.next())?),
+            "--reduce" => {
+                query.reducer = match args.next().map(|s| s.as_str()) {
+                    Some("product") => Reducer::Product,
+                    Some("sum") => Reducer::Sum,
+                    Some("max") => Reducer::Max,
+                    _ => return Err("--reduce needs one of product, sum, max".to_string()),
+                }
+            }
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+
+    let action = match action_s.as_str() {
+        "report" => SchematicAction::Report { json },
+        "gears" => SchematicAction::Gears(query),
+        "render" => SchematicAction::Render { svg },
+        other => return Err(format!("unknown schematic action '{}'", other)),
+    };
+    return Ok(Command::Schematic {
+        action,
+        source: source.unwrap_or(Source::File(input_path(3))),
+    });
+}
+
+fn parse_generate(args: &[String], level: &mut Level) -> Result<Command, String> {
+    let mut spec = DeckSpec::default();
+    let mut output: Option<PathBuf> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if parse_log_flag(arg, &mut args, level)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--cards" => spec.cards = parse_count(arg, args.next())?,
+            "--winning" => spec.winning = parse_count(arg, args.next())?,
+            "--own" => spec.own = parse_count(arg, args.next())?,
+            "--seed" => {
+                spec.seed = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or("--seed needs a number")?
+            }
+            "--win-probability" => {
+                spec.win_probability = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .filter(|p| (0.0..=1.0).contains(p))
+                    .ok_or("--win-probability needs a number between 0 and 1")?
+            }
+            "--output" | "-o" => {
+                output = Some(PathBuf::from(args.next().ok_or("--output needs a path")?))
+            }
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+    return Ok(Command::GenerateDeck { spec, output });
+}
+
+fn parse_scratchcards(args: &[String], level: &mut Level) -> Result<Command, String> {
+    let (action_s, args) = args
+        .split_first()
+        .ok_or("scratchcards needs an action: explain, check or generate")?;
+    if action_s == "generate" {
+        return parse_generate(args, level);
+    }
+    let mut source: Option<Source> = None;
+    let mut overrun = Overrun::default();
+    let mut mode = ParseMode::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if parse_log_flag(arg, &mut args, level)? || parse_source_flag(arg, &mut args, &mut source)?
+        {
+            continue;
+        }
+        match arg.as_str() {
+            "--overrun" => {
+                overrun = match args.next().map(|s| s.as_str()) {
+                    Some("clamp") => Overrun::Clamp,
+                    Some("error") => Overrun::Error,
+                    _ => return Err("--overrun needs clamp or error".to_string()),
+                }
+            }
+            "--strict" => mode = ParseMode::Strict,
+            other => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+
+    let action = match action_s.as_str() {
+        "explain" => ScratchcardsAction::Explain { overrun },
+        "check" => {
+            mode = ParseMode::Strict;
+            ScratchcardsAction::Check
+        }
+        other => return Err(format!("unknown scratchcards action '{}'", other)),
+    };
+    return Ok(Command::Scratchcards {
+        action,
+        mode,
+        source: source.unwrap_or(Source::File(input_path(4))),
+    });
+}
+
+// a single location or a `start..end` range
+fn parse_locations(s: &str) -> Result<Interval, String> {
+    let invalid = || format!("invalid location '{}', expected N or start..end", s);
+    return match s.split_once("..") {
+        Some((start, end)) => {
+            let start: i64 = start.parse().map_err(|_| invalid())?;
+            let end: i64 = end.parse().map_err(|_| invalid())?;
+            if start >= end {
+                return Err(format!(
+                    "empty location range '{}', the start must be below the end",
+                    s
+                ));
+            }
+            Ok(Interval::new(start, end))
+        }
+        None => {
+            let location: i64 = s.parse().map_err(|_| invalid())?;
+            let end = location.checked_add(1).ok_or_else(invalid)?;
+            Ok(Interval::new(location, end))
+        }
+    };
+}
+
+fn parse_almanac(args: &[String], level: &mut Level) -> Result<Command, String> {
+    let (action_s, args) = args
+        .split_first()
+        .ok_or("almanac needs an action: mapping, lookup, seeds or check")?;
+    let mut source: Option<Source> = None;
+    let mut from = d5::SEED.to_string();
+    let mut to = d5::LOCATION.to_string();
+    let mut locations: Option<Interval> = None;
+    let mut number: Option<i64> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if parse_log_flag(arg, &mut args, level)? || parse_source_flag(arg, &mut args, &mut source)?
+        {
+            continue;
+        }
+        match (action_s.as_str(), arg.as_str()) {
+            ("mapping" | "lookup", "--from") => {
+                from = args.next().ok_or("--from needs a category")?.clone()
+            }
+            ("mapping" | "lookup", "--to") => {
+                to = args.next().ok_or("--to needs a category")?.clone()
+            }
+            ("seeds", "--below") if locations.is_none() => {
+                let end = args
+                    .next()
+                    .and_then(|s| s.parse().ok())
+                    .filter(|end: &i64| *end > 0)
+                    .ok_or("--below needs a number above 0")?;
+                locations = Some(Interval::new(0, end));
+            }
+            ("seeds", s) if locations.is_none() && is_number(s) => {
+                locations = Some(parse_locations(s)?)
+            }
+            ("lookup", s) if number.is_none() && is_number(s) => {
+                number = Some(s.parse().map_err(|_| format!("invalid number '{}'", s))?)
+            }
+            (_, other) => return Err(format!("unexpected argument '{}'", other)),
+        }
+    }
+
+    let action = match action_s.as_str() {
+        "mapping" => AlmanacAction::Mapping { from, to },
+        "lookup" => AlmanacAction::Lookup {
+            number: number.ok_or("almanac lookup needs a number")?,
+            from,
+            to,
+        },
+        "seeds" => AlmanacAction::Seeds(
+            locations.ok_or("almanac seeds needs a location, a start..end range or --below")?,
+        ),
+        "check" => AlmanacAction::Check,
+        other => return Err(format!("unknown almanac action '{}'", other)),
+    };
+    return Ok(Command::Almanac {
+        action,
+        source: source.unwrap_or(Source::File(input_path(5))),
+    });
+}
+
+fn parse_verify(args: &[String], level: &mut Level) -> Result<Command, String> {
+    let mut rest = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !parse_log_flag(arg, &mut args, level)? {
+            rest.push(arg);
+        }
+    }
+    return match rest[..] {
+        [] => Ok(Command::Verify {
+            days: registry::days(),
+        }),
+        [all] if all == "--all" => Ok(Command::Verify {
+            days: registry::days(),
+        }),
+        [day] => Ok(Command::Verify {
+            days: vec![parse_day(day)?],
+        }),
+        _ => Err("verify takes a single day or --all".to_string()),
+    };
+}
+
+/// Parses the command and the log level its `-v` or `--log` flags ask for, which may also
+/// come before the command.
+pub fn parse_args(args: &[String]) -> Result<(Command, Level), String> {
+    let mut level = Level::default();
+    let mut args = args;
+    loop {
+        let mut rest = args.iter();
+        match rest.next() {
+            Some(arg) if parse_log_flag(arg, &mut rest, &mut level)? => args = rest.as_slice(),
+            _ => break,
+        }
+    }
+    let command = match args.first().map(|s| s.as_str()) {
+        None | Some("help") | Some("--help") | Some("-h") => Command::Help,
+        Some("list") => Command::List,
+        Some("run") => parse_run(&args[1..], &mut level)?,
+        Some("verify") => parse_verify(&args[1..], &mut level)?,
+        Some("bench") => parse_bench(&args[1..], &mut level)?,
+        Some("schematic") => parse_schematic(&args[1..], &mut level)?,
+        Some("scratchcards") => parse_scratchcards(&args[1..], &mut level)?,
+        Some("almanac") => parse_almanac(&args[1..], &mut level)?,
+        Some(day) if day.chars().all(|c| c.is_ascii_digit()) => parse_run(args, &mut level)?,
+        Some(other) => return Err(format!("unknown command '{}'", other)),
+    };
+    return Ok((command, level));
+}
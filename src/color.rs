@@ -0,0 +1,25 @@
+use std::io::{self, IsTerminal};
+
+pub const RED: &str = "31";
+pub const GREEN: &str = "32";
+pub const YELLOW: &str = "33";
+pub const CYAN: &str = "36";
+pub const BOLD_YELLOW: &str = "1;33";
+pub const DIM: &str = "2";
+
+// colors only when writing to a terminal and NO_COLOR is unset
+pub fn enabled() -> bool {
+    return io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+}
+
+// always colors, for output that is explicitly asked to be colored
+pub fn wrap(s: &str, color: &str) -> String {
+    return format!("\x1b[{}m{}\x1b[0m", color, s);
+}
+
+pub fn paint(s: &str, color: &str) -> String {
+    if !enabled() {
+        return s.to_string();
+    }
+    return wrap(s, color);
+}
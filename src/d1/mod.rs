@@ -1,4 +1,137 @@
-// Define a function to solve the code advent problem
-pub fn solve() {
-    // Your code advent solution goes here
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+const DUMMY_INPUT_P1: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+const DUMMY_INPUT_P2: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+const DIGIT_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+// the digit `s` starts with, also reading spelled-out digits when `words` is set
+pub fn digit_at(s: &str, words: bool) -> Option<u32> {
+    let first = s.chars().next()?;
+    if let Some(digit) = first.to_digit(10) {
+        return Some(digit);
+    }
+    if !words {
+        return None;
+    }
+    return DIGIT_WORDS
+        .iter()
+        .position(|word| s.starts_with(word))
+        .map(|i| i as u32 + 1);
+}
+
+// looking for a digit at every offset, instead of replacing words, keeps overlapping words like
+// "twone" readable both ways
+pub fn calibration_value(line: &str, words: bool) -> Option<u32> {
+    let mut digits = line
+        .char_indices()
+        .filter_map(|(i, _)| digit_at(&line[i..], words));
+    let first = digits.next()?;
+    let last = digits.next_back().unwrap_or(first);
+    return Some(first * 10 + last);
+}
+
+pub fn read_input(s: &str) -> Result<Vec<&str>> {
+    return s
+        .lines()
+        .enumerate()
+        .map(|(line_num, line)| {
+            if calibration_value(line, true).is_none() {
+                return Err(Error::parse(line_num + 1, "line has no digits"));
+            }
+            return Ok(line);
+        })
+        .collect();
+}
+
+// lines holding only spelled-out digits add nothing in part one
+pub fn solve_part_1(lines: &[&str]) -> u32 {
+    return lines
+        .iter()
+        .map(|line| calibration_value(line, false).unwrap_or(0))
+        .sum();
+}
+
+pub fn solve_part_2(lines: &[&str]) -> u32 {
+    return lines
+        .iter()
+        .filter_map(|line| calibration_value(line, true))
+        .sum();
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+    const EXAMPLE: &'static str = DUMMY_INPUT_P1;
+    const EXAMPLE_P2: &'static str = DUMMY_INPUT_P2;
+
+    type Input<'a> = Vec<&'a str>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Vec<&str>> {
+        return read_input(input);
+    }
+    fn part1(input: &Vec<&str>) -> Result<u32> {
+        return Ok(solve_part_1(input));
+    }
+    fn part2(input: &Vec<&str>) -> Result<u32> {
+        return Ok(solve_part_2(input));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_part_1() {
+        let input = Day1::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day1::part1(&input).unwrap(), 142);
+    }
+
+    #[test]
+    fn example_part_2() {
+        let input = Day1::parse(DUMMY_INPUT_P2).unwrap();
+        assert_eq!(Day1::part2(&input).unwrap(), 281);
+    }
+
+    #[test]
+    fn single_digit_is_first_and_last() {
+        assert_eq!(calibration_value("treb7uchet", false), Some(77));
+    }
+
+    #[test]
+    fn overlapping_words_count_both_ways() {
+        assert_eq!(calibration_value("twone", true), Some(21));
+        assert_eq!(calibration_value("eightwo", true), Some(82));
+        assert_eq!(calibration_value("xtwone3four", true), Some(24));
+        assert_eq!(calibration_value("oneight", true), Some(18));
+    }
+
+    #[test]
+    fn words_are_ignored_in_part_1() {
+        assert_eq!(calibration_value("two1nine", false), Some(11));
+        assert_eq!(calibration_value("eightwothree", false), None);
+    }
+
+    #[test]
+    fn line_without_digits_is_reported() {
+        let err = read_input("1abc2\nxyz").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: line has no digits");
+    }
 }
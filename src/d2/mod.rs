@@ -1,4 +1,271 @@
-// Define a function to solve the code advent problem
-pub fn solve() {
-    // Your code advent solution goes here
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+const DUMMY_INPUT_P1: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+/// The bag from the puzzle: 12 red, 13 green and 14 blue cubes.
+pub const BAG: Draw = Draw {
+    red: 12,
+    green: 13,
+    blue: 14,
+};
+
+/// A handful of cubes, also used for a bag's content and a game's minimum set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Draw {
+    pub red: u64,
+    pub green: u64,
+    pub blue: u64,
+}
+
+impl Draw {
+    pub fn fits_in(&self, bag: &Draw) -> bool {
+        return self.red <= bag.red && self.green <= bag.green && self.blue <= bag.blue;
+    }
+
+    pub fn max(&self, other: &Draw) -> Draw {
+        return Draw {
+            red: self.red.max(other.red),
+            green: self.green.max(other.green),
+            blue: self.blue.max(other.blue),
+        };
+    }
+
+    /// None when the product does not fit in a `u64`.
+    pub fn power(&self) -> Option<u64> {
+        return self.red.checked_mul(self.green)?.checked_mul(self.blue);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    pub fn is_possible(&self, bag: &Draw) -> bool {
+        return self.draws.iter().all(|draw| draw.fits_in(bag));
+    }
+
+    // the fewest cubes of each color that make every draw possible
+    pub fn minimum_set(&self) -> Draw {
+        return self
+            .draws
+            .iter()
+            .fold(Draw::default(), |acc, draw| acc.max(draw));
+    }
+}
+
+// 1-based column of the character at byte `offset` of `line`
+fn column(line: &str, offset: usize) -> usize {
+    return line.char_indices().take_while(|(i, _)| *i < offset).count() + 1;
+}
+
+// the pieces of `s` between `sep`s, each with its byte offset in a line where `s` begins at
+// `start`
+fn split_with_offsets(s: &str, start: usize, sep: char) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = start;
+    return s.split(sep).map(move |piece| {
+        let at = offset;
+        offset += piece.len() + sep.len_utf8();
+        return (at, piece);
+    });
+}
+
+// `draw_s` begins at byte `start` of `line`
+fn read_draw(line: &str, line_num: usize, start: usize, draw_s: &str) -> Result<Draw> {
+    let mut draw = Draw::default();
+    for (at, cubes_s) in split_with_offsets(draw_s, start, ',') {
+        let at = at + cubes_s.len() - cubes_s.trim_start().len();
+        let cubes_s = cubes_s.trim();
+        let (count_s, color) = cubes_s.split_once(' ').ok_or(Error::parse_at(
+            line_num,
+            column(line, at),
+            "expected a count and a color",
+        ))?;
+        let count: u64 = count_s
+            .parse()
+            .map_err(|_| Error::parse_at(line_num, column(line, at), "expected a cube count"))?;
+        let total = match color {
+            "red" => &mut draw.red,
+            "green" => &mut draw.green,
+            "blue" => &mut draw.blue,
+            _ => {
+                return Err(Error::parse_at(
+                    line_num,
+                    column(line, at + count_s.len() + 1),
+                    format!("unknown color '{}'", color),
+                ))
+            }
+        };
+        *total = total.checked_add(count).ok_or(Error::parse_at(
+            line_num,
+            column(line, at),
+            format!("too many {} cubes in one draw", color),
+        ))?;
+    }
+    return Ok(draw);
+}
+
+pub fn read_game(line: &str, line_num: usize) -> Result<Game> {
+    const PREFIX: &str = "Game ";
+    let (id_s, draws_s) = line
+        .strip_prefix(PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or(Error::parse(line_num, "expected 'Game N:'"))?;
+    let id = id_s
+        .parse()
+        .map_err(|_| Error::parse_at(line_num, column(line, PREFIX.len()), "expected a game id"))?;
+    let draws = split_with_offsets(draws_s, PREFIX.len() + id_s.len() + 1, ';')
+        .map(|(start, draw_s)| read_draw(line, line_num, start, draw_s))
+        .collect::<Result<Vec<Draw>>>()?;
+    return Ok(Game { id, draws });
+}
+
+pub fn read_input(s: &str) -> Result<Vec<Game>> {
+    return s
+        .lines()
+        .enumerate()
+        .map(|(line_num, line)| read_game(line, line_num + 1))
+        .collect();
+}
+
+pub fn sum_possible_ids(games: &[Game], bag: &Draw) -> u64 {
+    return games
+        .iter()
+        .filter(|game| game.is_possible(bag))
+        .map(|game| u64::from(game.id))
+        .sum();
+}
+
+pub fn solve_part_1(games: &[Game]) -> u64 {
+    return sum_possible_ids(games, &BAG);
+}
+
+pub fn solve_part_2(games: &[Game]) -> Result<u64> {
+    return games.iter().try_fold(0u64, |total, game| {
+        let power = game.minimum_set().power().ok_or(Error::Query(format!(
+            "the power of game {}'s minimum set overflows a 64-bit number",
+            game.id
+        )))?;
+        total.checked_add(power).ok_or(Error::Query(
+            "the sum of the powers overflows a 64-bit number".to_string(),
+        ))
+    });
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+    const EXAMPLE: &'static str = DUMMY_INPUT_P1;
+
+    type Input<'a> = Vec<Game>;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Vec<Game>> {
+        return read_input(input);
+    }
+    fn part1(input: &Vec<Game>) -> Result<u64> {
+        return Ok(solve_part_1(input));
+    }
+    fn part2(input: &Vec<Game>) -> Result<u64> {
+        return solve_part_2(input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day2::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day2::part1(&input).unwrap(), 8);
+        assert_eq!(Day2::part2(&input).unwrap(), 2286);
+    }
+
+    #[test]
+    fn game_is_parsed_into_draws() {
+        let game = read_game("Game 12: 3 blue, 4 red; 2 green", 1).unwrap();
+        assert_eq!(game.id, 12);
+        assert_eq!(
+            game.draws,
+            vec![
+                Draw {
+                    red: 4,
+                    green: 0,
+                    blue: 3
+                },
+                Draw {
+                    red: 0,
+                    green: 2,
+                    blue: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn bag_limit_is_configurable() {
+        let games = read_input(DUMMY_INPUT_P1).unwrap();
+        let small_bag = Draw {
+            red: 4,
+            green: 3,
+            blue: 6,
+        };
+        assert_eq!(sum_possible_ids(&games, &small_bag), 1 + 2);
+    }
+
+    #[test]
+    fn minimum_set() {
+        let games = read_input(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(
+            games[2].minimum_set(),
+            Draw {
+                red: 20,
+                green: 13,
+                blue: 6
+            }
+        );
+        assert_eq!(games[2].minimum_set().power(), Some(1560));
+    }
+
+    #[test]
+    fn unknown_color_is_reported() {
+        let err = read_input("Game 1: 3 blue, 4 purple").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 19: unknown color 'purple'");
+    }
+
+    #[test]
+    fn power_overflow_is_reported() {
+        let games = read_input("Game 1: 5000 red, 5000 green, 5000 blue").unwrap();
+        assert_eq!(solve_part_2(&games).unwrap(), 125_000_000_000);
+        let games =
+            read_input("Game 7: 4000000000 red, 4000000000 green, 4000000000 blue").unwrap();
+        assert_eq!(
+            solve_part_2(&games).unwrap_err().to_string(),
+            "the power of game 7's minimum set overflows a 64-bit number"
+        );
+        let err = read_input("Game 1: 18446744073709551615 red, 1 red").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 35: too many red cubes in one draw"
+        );
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        // the no-break space is two bytes but one column
+        let err = read_input("Game 1:\u{a0}3 blue, 4 purple").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 19: unknown color 'purple'");
+        let err = read_input("Game 1:\u{a0}3 blue; x red").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 17: expected a cube count");
+    }
 }
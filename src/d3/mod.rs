@@ -1,10 +1,15 @@
+use std::collections::BTreeSet;
 
-use regex::Regex;
-use std::collections::{HashMap};
-use std::path::Path;
-use std::{fs, vec};
+use crate::error::Result;
+use crate::grid::{Grid, Span, Stencil};
+use crate::scan;
+use crate::solution::Solution;
 
-const DAY: i8 = 3;
+pub mod query;
+pub mod render;
+pub mod report;
+
+use query::GearQuery;
 
 const DUMMY_INPUT_P1: &str = "467..114..
 ...*......
@@ -17,187 +22,211 @@ const DUMMY_INPUT_P1: &str = "467..114..
 ...$.*....
 .664.598..";
 
-// create a type alias for the matrix
-
-fn get_numbers_and_matrix_from_str(s: &str) -> (HashMap<(usize, usize), String>, Vec<String>) {
-    let lines: Vec<&str> = s.lines().collect();
+// symbols touch numbers across corners too
+const ADJACENCY: Stencil = Stencil::Moore;
 
-    let mut numbers: HashMap<(usize, usize), String> = HashMap::new();
+pub struct NumberInMatrix {
+    // (row, column) of the first digit
+    pub id: (usize, usize),
+    pub number: i32,
+    pub len: usize,
+}
 
-    for (row_num, line) in lines.iter().enumerate() {
-        let re = Regex::new(r"(\d+)").unwrap();
-        numbers.extend(
-            re.captures_iter(line)
-                .map(|cap| ((cap.get(0).unwrap().start(), row_num), cap[0].to_string())),
-        );
+impl NumberInMatrix {
+    pub fn span(&self) -> Span {
+        let (y, x) = self.id;
+        return Span {
+            x,
+            y,
+            len: self.len,
+        };
     }
-    return (numbers, lines.iter().map(|x| x.to_string()).collect());
 }
 
-struct NumberInMatrix {
-    id: (usize, usize),
-    number: i32,
+pub struct Gear {
+    pub x: usize,
+    pub y: usize,
+    pub symbol: char,
 }
 
-struct Gear {
-    x: usize,
-    y: usize,
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub numbers: Vec<NumberInMatrix>,
+    // for every cell covered by a digit, the index of its number in `numbers`
+    pub numbers_matrix: Grid<Option<usize>>,
 }
 
-fn get_gears_numbers_and_matrix_from_str(s: &str) -> (Vec<Gear>, Vec<Vec<Option<NumberInMatrix>>>) {
-    let lines: Vec<&str> = s.lines().collect();
-
-    let mut numbers: Vec<Vec<Option<NumberInMatrix>>> = vec![];
-    let mut gears = vec![];
-
-    for (row_num, line) in lines.iter().enumerate() {
-        let num_re = Regex::new(r"(\d+)").unwrap();
-        numbers.push((0..line.len()).map(|_x| None).collect()); // initialize the row with no values
-        num_re.captures_iter(line).for_each(|cap| {
-            let x = cap.get(0).unwrap().start();
-            let curr_number = cap[0].parse::<i32>().unwrap();
-            let number_len = cap[0].len();
-            for i in x..(x + number_len) {
-                numbers[row_num][i] = Some(NumberInMatrix {
-                    id: (row_num, x),
-                    number: curr_number,
-                });
-            }
-        });
-        gears.extend(line.chars().enumerate().filter_map(|(i, c)| {
-            if c != '*' {
-                return None;
+pub fn read_input(s: &str) -> Result<Schematic> {
+    let grid = Grid::parse(s)?;
+    let mut numbers = vec![];
+    let mut numbers_matrix = Grid::filled(grid.width(), grid.height(), None);
+
+    for (row_num, line) in s.lines().enumerate() {
+        for token in scan::numbers(line) {
+            let number = token.parse::<i32>(row_num + 1)?;
+            let len = token.text.len();
+            for x in token.index..token.index + len {
+                numbers_matrix[(x, row_num)] = Some(numbers.len());
             }
-            return Some(Gear { x: i, y: row_num });
-        }))
+            numbers.push(NumberInMatrix {
+                id: (row_num, token.index),
+                number,
+                len,
+            });
+        }
     }
-    return (gears, numbers);
+    return Ok(Schematic {
+        grid,
+        numbers,
+        numbers_matrix,
+    });
 }
 
-fn get_adjacent_indexes(
-    x: i32,
-    y: i32,
-    length: i32,
-    max_x: i32,
-    max_y: i32,
-) -> Vec<(usize, usize)> {
-    let mut indexes: Vec<(i32, i32)> = vec![];
-    let mut walked_indexes: Vec<(i32, i32)> = vec![];
-    let y_range = (y - 1).max(0)..=(y + 1).min(max_y);
-    for j in y_range {
-        let x_range = (x - 1).max(0)..=(x + length).min(max_x);
-        for i in x_range {
-            walked_indexes.push((i, j));
-            if (y == j) && (x <= i) && (i < x + length) {
-                let _b = 1;
-                continue;
-            }
-            indexes.push((i, j));
-        }
-    }
-    return indexes
-        .into_iter()
-        .map(|(x, y)| (x as usize, y as usize))
+// every cell holding one of `symbols`, row by row
+pub fn get_gears(grid: &Grid<char>, symbols: &[char]) -> Vec<Gear> {
+    return grid
+        .iter()
+        .filter(|(_, c)| symbols.contains(c))
+        .map(|((x, y), c)| Gear { x, y, symbol: *c })
         .collect();
 }
 
-fn is_part_number(mx: &Vec<String>, x: usize, y: usize, digits: &str) -> bool {
-    // filter m to get adiacent elements of x,y
-    // slice matrix to a get a matrix arout x,y knowing that x,y is the leftmost element of string digits
-    let x = x as i32;
-    let y = y as i32;
-    let max_x_idx = (mx[0].len() - 1) as i32;
-    let max_y_idx = (mx.len() - 1) as i32;
-    let adjacent_indexes = get_adjacent_indexes(x, y, digits.len() as i32, max_x_idx, max_y_idx);
-    let close_chars: Vec<char> = adjacent_indexes
-        .into_iter()
-        .map(|(x, y)| mx[y].chars().nth(x).unwrap())
-        .collect();
+pub fn is_symbol(c: char) -> bool {
+    return !c.is_ascii_digit() && c != '.';
+}
 
-    let first_symbol = close_chars
+pub fn is_part_number(grid: &Grid<char>, number: &NumberInMatrix) -> bool {
+    return grid
+        .neighbours(number.span(), &ADJACENCY)
         .into_iter()
-        .find(|c| !c.is_digit(10) && (c != &'.'));
-
-    return first_symbol.is_some();
+        .any(|(x, y)| is_symbol(grid[(x, y)]));
 }
 
-fn get_gear_ratio(gear: &Gear, numbers_matrix: &Vec<Vec<Option<NumberInMatrix>>>) -> i32 {
-    // list of (x,y)
-    let adj_idxs: Vec<(usize, usize)> = get_adjacent_indexes(
-        gear.x as i32,
-        gear.y as i32,
-        1,
-        (numbers_matrix.len() - 1) as i32,
-        (numbers_matrix[0].len() - 1) as i32,
-    );
-
-    let _nums: HashMap<(usize, usize), i32> = adj_idxs
+pub fn adjacent_numbers<'a>(gear: &Gear, schematic: &'a Schematic) -> Vec<&'a NumberInMatrix> {
+    let numbers_matrix = &schematic.numbers_matrix;
+    let adj_idxs = numbers_matrix.neighbours(Span::cell(gear.x, gear.y), &ADJACENCY);
+
+    // a number spanning several adjacent cells still counts once
+    let nums: BTreeSet<usize> = adj_idxs
         .iter()
-        .filter_map(|(x, y)| {
-            // filter only numbers adjacent numbers in matrix
-            let n: &NumberInMatrix = numbers_matrix[*y][*x].as_ref()?;
-            return Some((n.id, n.number));
-        })
+        .filter_map(|(x, y)| numbers_matrix[(*x, *y)])
         .collect();
 
-    if _nums.len() == 2 {
-        return _nums.values().into_iter().fold(1, |a, b| a * b);
-    }
-    return 0;
+    return nums.iter().map(|i| &schematic.numbers[*i]).collect();
 }
 
-fn solve_part_2(input: &str) -> i32 {
-    let (gears, numbers_matrix) = get_gears_numbers_and_matrix_from_str(input);
-    let ratios = gears
+pub fn solve_part_2(schematic: &Schematic) -> Result<i64> {
+    return GearQuery::gear_ratios().total(schematic);
+}
+
+pub fn solve_part_1(schematic: &Schematic) -> i64 {
+    return schematic
+        .numbers
         .iter()
-        .map(|gear| get_gear_ratio(gear, &numbers_matrix));
-    return ratios.into_iter().sum();
+        .filter(|number| is_part_number(&schematic.grid, number))
+        .map(|number| number.number as i64)
+        .sum();
 }
 
-fn solve_part_1(input: &str) -> i32 {
-    let (numbers, m) = get_numbers_and_matrix_from_str(input);
-    let mut sum_of_parts = 0;
-    for ((x, y), digits) in numbers {
-        if is_part_number(&m, x, y, digits.as_str()) {
-            sum_of_parts += digits.parse::<i32>().unwrap();
-        }
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+    const EXAMPLE: &'static str = DUMMY_INPUT_P1;
+
+    type Input<'a> = Schematic;
+    type Answer = i64;
+
+    fn parse(input: &str) -> Result<Schematic> {
+        return read_input(input);
+    }
+    fn part1(input: &Schematic) -> Result<i64> {
+        return Ok(solve_part_1(input));
+    }
+    fn part2(input: &Schematic) -> Result<i64> {
+        return solve_part_2(input);
     }
-    return sum_of_parts;
 }
 
-fn gather_input_and_solve_p1() {
-    let res = solve_part_1(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
-    // read a string from file input.txt
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
-
-    let res = solve_part_1(input.as_str());
-    println!("Result: {}", res);
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-fn gather_input_and_solve_p2() {
-    let res = solve_part_2(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
-    // read a string from file input.txt
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
-
-    let res = solve_part_2(input.as_str());
-    println!("Result: {}", res);
-}
+    #[test]
+    fn example() {
+        let input = Day3::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day3::part1(&input).unwrap(), 4361);
+        assert_eq!(Day3::part2(&input).unwrap(), 467835);
+    }
 
-// Define a function to solve the code advent problem
-pub fn solve() {
-    gather_input_and_solve_p1();
-    gather_input_and_solve_p2();
-}
+    #[test]
+    fn real_input() {
+        let input = Day3::parse(include_str!("../../resources/d3/input.txt")).unwrap();
+        assert_eq!(Day3::part1(&input).unwrap(), 539590);
+        assert_eq!(Day3::part2(&input).unwrap(), 80703636);
+    }
 
-// Define the main function to run the code advent solution
-fn main() {
-    solve();
+    #[test]
+    fn wide_schematic() {
+        let input = Day3::parse(".....7.\n......*\n.....12").unwrap();
+        assert_eq!(Day3::part1(&input).unwrap(), 19);
+        assert_eq!(Day3::part2(&input).unwrap(), 84);
+    }
+
+    #[test]
+    fn tall_schematic() {
+        let input = Day3::parse("4.\n*.\n.3\n..\n9#").unwrap();
+        assert_eq!(Day3::part1(&input).unwrap(), 16);
+        assert_eq!(Day3::part2(&input).unwrap(), 12);
+    }
+
+    #[test]
+    fn numbers_are_found_with_their_span() {
+        let schematic = read_input(DUMMY_INPUT_P1).unwrap();
+        let first: Vec<((usize, usize), i32, usize)> = schematic.numbers[..3]
+            .iter()
+            .map(|n| (n.id, n.number, n.len))
+            .collect();
+        assert_eq!(
+            first,
+            vec![((0, 0), 467, 3), ((0, 5), 114, 3), ((2, 2), 35, 2)]
+        );
+        assert_eq!(schematic.numbers_matrix[(6, 2)], Some(3));
+        assert_eq!(schematic.numbers_matrix[(3, 0)], None);
+    }
+
+    #[test]
+    fn part_numbers_need_an_adjacent_symbol() {
+        let schematic = read_input(DUMMY_INPUT_P1).unwrap();
+        let parts: Vec<bool> = schematic
+            .numbers
+            .iter()
+            .map(|number| is_part_number(&schematic.grid, number))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![true, false, true, true, true, false, true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn gears_and_their_numbers() {
+        let schematic = read_input(DUMMY_INPUT_P1).unwrap();
+        let gears: Vec<Vec<i32>> = get_gears(&schematic.grid, &['*'])
+            .iter()
+            .map(|gear| {
+                adjacent_numbers(gear, &schematic)
+                    .iter()
+                    .map(|n| n.number)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(gears, vec![vec![467, 35], vec![617], vec![755, 598]]);
+    }
+
+    #[test]
+    fn ragged_schematic_is_rejected() {
+        assert!(Day3::parse("..1\n.*\n").is_err());
+    }
 }
@@ -0,0 +1,205 @@
+use super::{adjacent_numbers, get_gears, Schematic};
+use crate::error::{Error, Result};
+
+/// How many numbers a symbol must touch to be counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn matches(&self, count: usize) -> bool {
+        return match self {
+            Arity::Exactly(n) => count == *n,
+            Arity::AtLeast(n) => count >= *n,
+        };
+    }
+}
+
+/// How the numbers around one symbol are combined into its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reducer {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Reducer {
+    fn name(&self) -> &'static str {
+        return match self {
+            Reducer::Product => "product",
+            Reducer::Sum => "sum",
+            Reducer::Max => "max",
+        };
+    }
+
+    /// None when the product or sum does not fit in an `i64`.
+    pub fn reduce(&self, numbers: &[i64]) -> Option<i64> {
+        return match self {
+            Reducer::Product => numbers.iter().try_fold(1i64, |acc, n| acc.checked_mul(*n)),
+            Reducer::Sum => numbers.iter().try_fold(0i64, |acc, n| acc.checked_add(*n)),
+            Reducer::Max => Some(numbers.iter().copied().max().unwrap_or(0)),
+        };
+    }
+}
+
+/// A symbol cell selected by a query, with the numbers around it and their reduced value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearMatch {
+    pub x: usize,
+    pub y: usize,
+    pub symbol: char,
+    pub numbers: Vec<i64>,
+    pub value: i64,
+}
+
+/// Selects symbol cells by the numbers touching them and aggregates their reduced values;
+/// part two is the query `*`, exactly two numbers, product.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearQuery {
+    pub symbols: Vec<char>,
+    pub arity: Arity,
+    pub reducer: Reducer,
+}
+
+impl GearQuery {
+    pub fn gear_ratios() -> GearQuery {
+        return GearQuery {
+            symbols: vec!['*'],
+            arity: Arity::Exactly(2),
+            reducer: Reducer::Product,
+        };
+    }
+
+    /// Errors when the numbers around a symbol reduce to more than an `i64` holds.
+    pub fn matches(&self, schematic: &Schematic) -> Result<Vec<GearMatch>> {
+        let mut matches = vec![];
+        for gear in get_gears(&schematic.grid, &self.symbols) {
+            let numbers: Vec<i64> = adjacent_numbers(&gear, schematic)
+                .iter()
+                .map(|n| n.number as i64)
+                .collect();
+            if !self.arity.matches(numbers.len()) {
+                continue;
+            }
+            let value = self.reducer.reduce(&numbers).ok_or(Error::Query(format!(
+                "the {} of the numbers around '{}' at {}, {} overflows a 64-bit number",
+                self.reducer.name(),
+                gear.symbol,
+                gear.x,
+                gear.y
+            )))?;
+            matches.push(GearMatch {
+                x: gear.x,
+                y: gear.y,
+                symbol: gear.symbol,
+                value,
+                numbers,
+            });
+        }
+        return Ok(matches);
+    }
+
+    // sum of the reduced values over every matching symbol
+    pub fn total(&self, schematic: &Schematic) -> Result<i64> {
+        return self.matches(schematic)?.iter().try_fold(0i64, |total, m| {
+            total.checked_add(m.value).ok_or(Error::Query(
+                "the total of the matching symbols overflows a 64-bit number".to_string(),
+            ))
+        });
+    }
+}
+
+pub fn to_table(matches: &[GearMatch]) -> String {
+    let mut out = format!(
+        "{:>4}  {:>4}  {:<6}  {:<20}  {:>12}\n",
+        "x", "y", "Symbol", "Numbers", "Value"
+    );
+    for m in matches {
+        let numbers: Vec<String> = m.numbers.iter().map(|n| n.to_string()).collect();
+        out += &format!(
+            "{:>4}  {:>4}  {:<6}  {:<20}  {:>12}\n",
+            m.x,
+            m.y,
+            m.symbol,
+            numbers.join(" "),
+            m.value
+        );
+    }
+    // every value fits, their sum only needs to for display
+    let total: i128 = matches.iter().map(|m| i128::from(m.value)).sum();
+    out += &format!("\n{} matching symbols, total {}\n", matches.len(), total);
+    return out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{read_input, DUMMY_INPUT_P1};
+    use super::*;
+
+    #[test]
+    fn gear_ratios_is_part_two() {
+        let schematic = read_input(DUMMY_INPUT_P1).unwrap();
+        let matches = GearQuery::gear_ratios().matches(&schematic).unwrap();
+        let values: Vec<(usize, usize, i64)> =
+            matches.iter().map(|m| (m.x, m.y, m.value)).collect();
+        assert_eq!(values, vec![(3, 1, 16345), (5, 8, 451490)]);
+        assert_eq!(GearQuery::gear_ratios().total(&schematic).unwrap(), 467835);
+    }
+
+    #[test]
+    fn symbols_arity_and_reducer_are_configurable() {
+        let schematic = read_input(DUMMY_INPUT_P1).unwrap();
+        let query = GearQuery {
+            symbols: vec!['*', '#', '+'],
+            arity: Arity::AtLeast(1),
+            reducer: Reducer::Sum,
+        };
+        // 467+35, 617, 633, 592, 755+598
+        assert_eq!(
+            query.total(&schematic).unwrap(),
+            502 + 617 + 633 + 592 + 1353
+        );
+
+        let query = GearQuery {
+            symbols: vec!['*'],
+            arity: Arity::Exactly(1),
+            reducer: Reducer::Max,
+        };
+        assert_eq!(query.total(&schematic).unwrap(), 617);
+    }
+
+    #[test]
+    fn three_way_gear() {
+        let schematic = read_input("2.3\n.*.\n..4").unwrap();
+        let query = GearQuery {
+            arity: Arity::Exactly(3),
+            ..GearQuery::gear_ratios()
+        };
+        assert_eq!(query.total(&schematic).unwrap(), 24);
+        assert_eq!(GearQuery::gear_ratios().total(&schematic).unwrap(), 0);
+    }
+
+    #[test]
+    fn reducers() {
+        assert_eq!(Reducer::Product.reduce(&[2, 3, 4]), Some(24));
+        assert_eq!(Reducer::Sum.reduce(&[2, 3, 4]), Some(9));
+        assert_eq!(Reducer::Max.reduce(&[2, 7, 4]), Some(7));
+        assert_eq!(Reducer::Max.reduce(&[]), Some(0));
+        assert_eq!(Reducer::Product.reduce(&[i64::MAX, 2]), None);
+    }
+
+    #[test]
+    fn overflowing_product_is_reported() {
+        let schematic = read_input("99999.99999\n.....*.....\n99999.99999").unwrap();
+        let query = GearQuery {
+            arity: Arity::AtLeast(2),
+            ..GearQuery::gear_ratios()
+        };
+        assert_eq!(
+            query.matches(&schematic).unwrap_err().to_string(),
+            "the product of the numbers around '*' at 5, 1 overflows a 64-bit number"
+        );
+    }
+}
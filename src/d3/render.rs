@@ -0,0 +1,205 @@
+use std::fmt::Write;
+
+use super::query::GearQuery;
+use super::{is_part_number, is_symbol, Schematic};
+use crate::color;
+use crate::grid::Grid;
+
+/// What a cell of the schematic is drawn as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// A digit of a number touching a symbol.
+    Part,
+    /// A digit of a number touching no symbol.
+    Rejected,
+    /// A `*` touching exactly two numbers.
+    Gear,
+    Symbol,
+    Blank,
+}
+
+pub fn marks(schematic: &Schematic) -> Grid<Mark> {
+    let grid = &schematic.grid;
+    let mut marks = grid.map(|c| {
+        if is_symbol(*c) {
+            Mark::Symbol
+        } else {
+            Mark::Blank
+        }
+    });
+    for number in &schematic.numbers {
+        let mark = if is_part_number(grid, number) {
+            Mark::Part
+        } else {
+            Mark::Rejected
+        };
+        let span = number.span();
+        for x in span.x..span.x + span.len {
+            marks[(x, span.y)] = mark;
+        }
+    }
+    // the product of two i32 numbers always fits, so gear ratios never overflow
+    for gear in GearQuery::gear_ratios()
+        .matches(schematic)
+        .unwrap_or_default()
+    {
+        marks[(gear.x, gear.y)] = Mark::Gear;
+    }
+    return marks;
+}
+
+fn ansi_color(mark: Mark) -> &'static str {
+    return match mark {
+        Mark::Part => color::GREEN,
+        Mark::Rejected => color::RED,
+        Mark::Gear => color::BOLD_YELLOW,
+        Mark::Symbol => color::CYAN,
+        Mark::Blank => color::DIM,
+    };
+}
+
+pub fn to_ansi(schematic: &Schematic) -> String {
+    let marks = marks(schematic);
+    let mut out = String::new();
+    for (y, row) in schematic.grid.rows().enumerate() {
+        // one escape sequence per run of equally marked cells keeps the output small
+        let mut x = 0;
+        while x < row.len() {
+            let mark = marks[(x, y)];
+            let start = x;
+            while x < row.len() && marks[(x, y)] == mark {
+                x += 1;
+            }
+            let text: String = row[start..x].iter().collect();
+            out += &color::wrap(&text, ansi_color(mark));
+        }
+        out.push('\n');
+    }
+    return out;
+}
+
+const CELL_WIDTH: usize = 10;
+const CELL_HEIGHT: usize = 16;
+
+fn svg_fill(mark: Mark) -> Option<&'static str> {
+    return match mark {
+        Mark::Part => Some("#b7e4b0"),
+        Mark::Rejected => Some("#f4a6a6"),
+        Mark::Gear => Some("#f7d774"),
+        Mark::Symbol => Some("#bde0f6"),
+        Mark::Blank => None,
+    };
+}
+
+fn svg_escape(c: char) -> String {
+    return match c {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        c => c.to_string(),
+    };
+}
+
+pub fn to_svg(schematic: &Schematic) -> String {
+    let marks = marks(schematic);
+    let grid = &schematic.grid;
+    let (width, height) = (grid.width() * CELL_WIDTH, grid.height() * CELL_HEIGHT);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+        width, height, width, height
+    )
+    .unwrap();
+    writeln!(out, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>").unwrap();
+    for ((x, y), mark) in marks.iter() {
+        if let Some(fill) = svg_fill(*mark) {
+            writeln!(
+                out,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                x * CELL_WIDTH,
+                y * CELL_HEIGHT,
+                CELL_WIDTH,
+                CELL_HEIGHT,
+                fill
+            )
+            .unwrap();
+        }
+    }
+    writeln!(
+        out,
+        "<g font-family=\"monospace\" font-size=\"14\" text-anchor=\"middle\">"
+    )
+    .unwrap();
+    for ((x, y), c) in grid.iter() {
+        if *c == '.' {
+            continue;
+        }
+        writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\">{}</text>",
+            x * CELL_WIDTH + CELL_WIDTH / 2,
+            y * CELL_HEIGHT + CELL_HEIGHT - 4,
+            svg_escape(*c)
+        )
+        .unwrap();
+    }
+    writeln!(out, "</g>").unwrap();
+    writeln!(out, "</svg>").unwrap();
+    return out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{read_input, DUMMY_INPUT_P1};
+    use super::*;
+
+    #[test]
+    fn cells_are_marked() {
+        let marks = marks(&read_input(DUMMY_INPUT_P1).unwrap());
+        assert_eq!(
+            marks.row(0)[..8],
+            [
+                Mark::Part,
+                Mark::Part,
+                Mark::Part,
+                Mark::Blank,
+                Mark::Blank,
+                Mark::Rejected,
+                Mark::Rejected,
+                Mark::Rejected
+            ]
+        );
+        assert_eq!(marks[(3, 1)], Mark::Gear);
+        // the '*' next to 617 alone is not a gear
+        assert_eq!(marks[(3, 4)], Mark::Symbol);
+        assert_eq!(marks[(6, 3)], Mark::Symbol);
+    }
+
+    #[test]
+    fn ansi_groups_runs_of_the_same_mark() {
+        let ansi = to_ansi(&read_input("12.\n.*3").unwrap());
+        assert_eq!(
+            ansi,
+            format!(
+                "{}{}\n{}{}{}\n",
+                color::wrap("12", color::GREEN),
+                color::wrap(".", color::DIM),
+                color::wrap(".", color::DIM),
+                color::wrap("*", color::BOLD_YELLOW),
+                color::wrap("3", color::GREEN)
+            )
+        );
+    }
+
+    #[test]
+    fn svg_has_a_cell_per_mark() {
+        let svg = to_svg(&read_input("1.\n.&").unwrap());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"10\" height=\"16\" fill=\"#b7e4b0\"/>"));
+        assert!(svg.contains("<text x=\"15\" y=\"28\">&amp;</text>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
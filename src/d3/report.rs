@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::{is_symbol, Schematic, ADJACENCY};
+use crate::json;
+
+/// One number of the schematic with the symbols around it.
+pub struct NumberReport {
+    pub number: i32,
+    pub x: usize,
+    pub y: usize,
+    pub len: usize,
+    /// Adjacent symbols with their `(x, y)` position, in row-major order.
+    pub symbols: Vec<((usize, usize), char)>,
+}
+
+impl NumberReport {
+    pub fn is_part(&self) -> bool {
+        return !self.symbols.is_empty();
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymbolStats {
+    /// How many cells hold this symbol.
+    pub count: usize,
+    /// How many numbers touch one of those cells, counted once per cell.
+    pub adjacent_numbers: usize,
+}
+
+/// How every number of a schematic was classified, to see why a sum comes out wrong.
+pub struct Report {
+    pub numbers: Vec<NumberReport>,
+    pub symbols: BTreeMap<char, SymbolStats>,
+}
+
+pub fn report(schematic: &Schematic) -> Report {
+    let grid = &schematic.grid;
+    let numbers: Vec<NumberReport> = schematic
+        .numbers
+        .iter()
+        .map(|number| {
+            let span = number.span();
+            let symbols = grid
+                .neighbours(span, &ADJACENCY)
+                .into_iter()
+                .map(|(x, y)| ((x, y), grid[(x, y)]))
+                .filter(|(_, c)| is_symbol(*c))
+                .collect();
+            NumberReport {
+                number: number.number,
+                x: span.x,
+                y: span.y,
+                len: span.len,
+                symbols,
+            }
+        })
+        .collect();
+
+    let mut symbols: BTreeMap<char, SymbolStats> = BTreeMap::new();
+    for (_, c) in grid.iter().filter(|(_, c)| is_symbol(**c)) {
+        symbols.entry(*c).or_default().count += 1;
+    }
+    for (_, c) in numbers.iter().flat_map(|number| &number.symbols) {
+        symbols.entry(*c).or_default().adjacent_numbers += 1;
+    }
+    return Report { numbers, symbols };
+}
+
+impl Report {
+    pub fn part_sum(&self) -> i64 {
+        return self
+            .numbers
+            .iter()
+            .filter(|number| number.is_part())
+            .map(|number| i64::from(number.number))
+            .sum();
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{:>8}  {:>4}  {:>4}  {:<8}  Symbols",
+            "Number", "x", "y", "Class"
+        )
+        .unwrap();
+        for number in &self.numbers {
+            let symbols: Vec<String> = number
+                .symbols
+                .iter()
+                .map(|((x, y), c)| format!("{}@{},{}", c, x, y))
+                .collect();
+            let class = if number.is_part() { "part" } else { "not part" };
+            let line = format!(
+                "{:>8}  {:>4}  {:>4}  {:<8}  {}",
+                number.number,
+                number.x,
+                number.y,
+                class,
+                symbols.join(" ")
+            );
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "{:>6}  {:>5}  {:>16}",
+            "Symbol", "Count", "Adjacent numbers"
+        )
+        .unwrap();
+        for (symbol, stats) in &self.symbols {
+            writeln!(
+                out,
+                "{:>6}  {:>5}  {:>16}",
+                symbol, stats.count, stats.adjacent_numbers
+            )
+            .unwrap();
+        }
+        let parts = self.numbers.iter().filter(|n| n.is_part()).count();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "{} of {} numbers are parts, summing to {}",
+            parts,
+            self.numbers.len(),
+            self.part_sum()
+        )
+        .unwrap();
+        return out;
+    }
+
+    pub fn to_json(&self) -> String {
+        let numbers: Vec<String> = self
+            .numbers
+            .iter()
+            .map(|number| {
+                let symbols: Vec<String> = number
+                    .symbols
+                    .iter()
+                    .map(|((x, y), c)| {
+                        format!(
+                            "{{\"symbol\": {}, \"x\": {}, \"y\": {}}}",
+                            json::quote(&c.to_string()),
+                            x,
+                            y
+                        )
+                    })
+                    .collect();
+                format!(
+                    "    {{\"number\": {}, \"x\": {}, \"y\": {}, \"len\": {}, \"part\": {}, \"symbols\": [{}]}}",
+                    number.number,
+                    number.x,
+                    number.y,
+                    number.len,
+                    number.is_part(),
+                    symbols.join(", ")
+                )
+            })
+            .collect();
+        let symbols: Vec<String> = self
+            .symbols
+            .iter()
+            .map(|(symbol, stats)| {
+                format!(
+                    "    {{\"symbol\": {}, \"count\": {}, \"adjacent_numbers\": {}}}",
+                    json::quote(&symbol.to_string()),
+                    stats.count,
+                    stats.adjacent_numbers
+                )
+            })
+            .collect();
+        return format!(
+            "{{\n  \"numbers\": [\n{}\n  ],\n  \"symbols\": [\n{}\n  ],\n  \"part_sum\": {}\n}}\n",
+            numbers.join(",\n"),
+            symbols.join(",\n"),
+            self.part_sum()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{read_input, DUMMY_INPUT_P1};
+    use super::*;
+
+    #[test]
+    fn numbers_are_classified() {
+        let report = report(&read_input(DUMMY_INPUT_P1).unwrap());
+        assert_eq!(report.part_sum(), 4361);
+        let rejected: Vec<i32> = report
+            .numbers
+            .iter()
+            .filter(|n| !n.is_part())
+            .map(|n| n.number)
+            .collect();
+        assert_eq!(rejected, vec![114, 58]);
+        assert_eq!(report.numbers[0].symbols, vec![((3, 1), '*')]);
+    }
+
+    #[test]
+    fn symbol_counts() {
+        let report = report(&read_input(DUMMY_INPUT_P1).unwrap());
+        let counts: Vec<(char, usize, usize)> = report
+            .symbols
+            .iter()
+            .map(|(c, stats)| (*c, stats.count, stats.adjacent_numbers))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![('#', 1, 1), ('$', 1, 1), ('*', 3, 5), ('+', 1, 1)]
+        );
+    }
+
+    #[test]
+    fn json_lists_every_number() {
+        let json = report(&read_input("1.\n.#").unwrap()).to_json();
+        assert!(json.contains(
+            "{\"number\": 1, \"x\": 0, \"y\": 0, \"len\": 1, \"part\": true, \"symbols\": [{\"symbol\": \"#\", \"x\": 1, \"y\": 1}]}"
+        ));
+        assert!(json.contains("\"part_sum\": 1"));
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt::Write;
+
+use super::{cascade, points, Deck, Overrun};
+use crate::error::Result;
+
+/// How one card scored and how many copies of it the cascade produced.
+pub struct CardExplanation {
+    pub id: u32,
+    pub matching: Vec<i32>,
+    pub points: u64,
+    pub copies: u64,
+    /// The earlier cards that won copies of this one, with how many copies each handed out.
+    pub won_from: Vec<(u32, u64)>,
+}
+
+pub fn explain(deck: &Deck, overrun: Overrun) -> Result<Vec<CardExplanation>> {
+    let copies = cascade(deck, overrun)?;
+    let points = points(deck)?;
+    let mut explanations: Vec<CardExplanation> = deck
+        .iter()
+        .zip(&copies)
+        .zip(&points)
+        .map(|((card, copies), points)| CardExplanation {
+            id: card.id,
+            matching: card.matching(),
+            points: *points,
+            copies: *copies,
+            won_from: vec![],
+        })
+        .collect();
+
+    for (i, card) in deck.iter().enumerate() {
+        // cards past the end of the deck cannot be won
+        let last = (i + card.matches()).min(deck.len() - 1);
+        for won in &mut explanations[i + 1..=last] {
+            won.won_from.push((card.id, copies[i]));
+        }
+    }
+    return Ok(explanations);
+}
+
+pub fn to_table(explanations: &[CardExplanation]) -> String {
+    let matching: Vec<String> = explanations
+        .iter()
+        .map(|card| {
+            let numbers: Vec<String> = card.matching.iter().map(|n| n.to_string()).collect();
+            format!("{}: {}", card.matching.len(), numbers.join(" "))
+        })
+        .collect();
+    let width = matching.iter().map(|m| m.len()).max().unwrap_or(0).max(7);
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "{:>5}  {:<width$}  {:>6}  {:>8}  Won from",
+        "Card", "Matches", "Points", "Copies"
+    )
+    .unwrap();
+    for (card, matching) in explanations.iter().zip(&matching) {
+        let won_from: Vec<String> = card
+            .won_from
+            .iter()
+            .map(|(id, copies)| format!("{} (x{})", id, copies))
+            .collect();
+        let line = format!(
+            "{:>5}  {:<width$}  {:>6}  {:>8}  {}",
+            card.id,
+            matching,
+            card.points,
+            card.copies,
+            won_from.join(", ")
+        );
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "{} points, {} cards in the end",
+        // the totals are only for show, u128 keeps them from overflowing like the answers can
+        explanations
+            .iter()
+            .map(|card| u128::from(card.points))
+            .sum::<u128>(),
+        explanations
+            .iter()
+            .map(|card| u128::from(card.copies))
+            .sum::<u128>()
+    )
+    .unwrap();
+    return out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{read_input, DUMMY_INPUT_P1};
+    use super::*;
+
+    #[test]
+    fn copies_and_where_they_came_from() {
+        let explanations = explain(&read_input(DUMMY_INPUT_P1).unwrap(), Overrun::Clamp).unwrap();
+        let copies: Vec<u64> = explanations.iter().map(|card| card.copies).collect();
+        assert_eq!(copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(explanations[3].won_from, vec![(1, 1), (2, 2), (3, 4)]);
+        assert!(explanations[5].won_from.is_empty());
+    }
+
+    #[test]
+    fn wins_past_the_last_card_are_dropped() {
+        let explanations = explain(
+            &read_input("Card 1: 1 2 | 1 2\nCard 2: 3 | 3").unwrap(),
+            Overrun::Clamp,
+        )
+        .unwrap();
+        let copies: Vec<u64> = explanations.iter().map(|card| card.copies).collect();
+        assert_eq!(copies, vec![1, 2]);
+    }
+
+    #[test]
+    fn table_lists_every_card() {
+        let table =
+            to_table(&explain(&read_input(DUMMY_INPUT_P1).unwrap(), Overrun::Clamp).unwrap());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[0],
+            " Card  Matches         Points    Copies  Won from"
+        );
+        assert_eq!(lines[1], "    1  4: 17 48 83 86       8         1");
+        assert_eq!(
+            lines[4],
+            "    4  1: 84                1         8  1 (x1), 2 (x2), 3 (x4)"
+        );
+        assert_eq!(lines[8], "13 points, 30 cards in the end");
+    }
+}
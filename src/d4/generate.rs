@@ -0,0 +1,211 @@
+/// Shape of a generated deck. Numbers are drawn from `1..=99`, or a wider range when the two
+/// sides together need more distinct numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckSpec {
+    pub cards: usize,
+    pub winning: usize,
+    pub own: usize,
+    /// Chance of each winning number also being one of mine.
+    pub win_probability: f64,
+    pub seed: u64,
+}
+
+impl Default for DeckSpec {
+    // the shape of the puzzle input
+    fn default() -> DeckSpec {
+        return DeckSpec {
+            cards: 211,
+            winning: 10,
+            own: 25,
+            win_probability: 0.2,
+            seed: 0,
+        };
+    }
+}
+
+// SplitMix64, small and good enough for test data
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return z ^ (z >> 31);
+    }
+
+    // uniform in 0..n
+    fn below(&mut self, n: usize) -> usize {
+        return ((self.next() as u128 * n as u128) >> 64) as usize;
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        return ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < p;
+    }
+
+    // partial Fisher-Yates: the first `n` items end up a random sample of the whole slice
+    fn sample<T>(&mut self, items: &mut [T], n: usize) {
+        for i in 0..n.min(items.len()) {
+            let j = i + self.below(items.len() - i);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// A deck that `read_deck` accepts in strict mode, where no card wins copies past the end.
+/// The same spec always gives the same deck.
+pub fn generate(spec: &DeckSpec) -> String {
+    let mut rng = Rng(spec.seed);
+    let largest = 99.max(spec.winning + spec.own);
+    let number_width = largest.to_string().len();
+    let id_width = spec.cards.to_string().len();
+    let mut pool: Vec<usize> = (1..=largest).collect();
+
+    let mut out = String::new();
+    for i in 0..spec.cards {
+        rng.sample(&mut pool, spec.winning + spec.own);
+        let (winning, others) = pool.split_at_mut(spec.winning);
+        let mut winning = winning.to_vec();
+
+        let remaining = spec.cards - i - 1;
+        let mut wins = (0..spec.winning)
+            .filter(|_| rng.chance(spec.win_probability))
+            .count();
+        wins = wins.min(spec.own).min(remaining);
+        rng.sample(&mut winning, wins);
+        let mut own: Vec<usize> = winning[..wins]
+            .iter()
+            .chain(&others[..spec.own - wins])
+            .cloned()
+            .collect();
+        let own_len = own.len();
+        rng.sample(&mut own, own_len);
+        rng.sample(&mut winning, spec.winning);
+
+        let side = |numbers: &[usize]| {
+            return numbers
+                .iter()
+                .map(|n| format!("{:>number_width$}", n))
+                .collect::<Vec<String>>()
+                .join(" ");
+        };
+        out += &format!(
+            "Card {:>id_width$}: {} | {}\n",
+            i + 1,
+            side(&winning),
+            side(&own)
+        );
+    }
+    return out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        count_cards, read_deck, solve_part_1, solve_part_2, Deck, Overrun, ParseMode,
+    };
+    use super::*;
+
+    fn spec(cards: usize, win_probability: f64, seed: u64) -> DeckSpec {
+        return DeckSpec {
+            cards,
+            win_probability,
+            seed,
+            ..DeckSpec::default()
+        };
+    }
+
+    // the cascade worked backwards: a card is itself plus everything its wins bring in
+    fn backwards_count(deck: &Deck) -> u64 {
+        let mut brings = vec![0u64; deck.len()];
+        for i in (0..deck.len()).rev() {
+            let wins = deck[i].matches().min(deck.len() - i - 1);
+            brings[i] = 1 + brings[i + 1..=i + wins].iter().sum::<u64>();
+        }
+        return brings.iter().sum();
+    }
+
+    #[test]
+    fn same_seed_same_deck() {
+        assert_eq!(generate(&spec(50, 0.3, 7)), generate(&spec(50, 0.3, 7)));
+        assert_ne!(generate(&spec(50, 0.3, 7)), generate(&spec(50, 0.3, 8)));
+    }
+
+    #[test]
+    fn decks_are_strictly_valid() {
+        let deck = read_deck(&generate(&DeckSpec::default()), ParseMode::Strict).unwrap();
+        assert_eq!(deck.len(), 211);
+        assert_eq!((deck[0].winning.len(), deck[0].mine.len()), (10, 25));
+        assert!(count_cards(&deck, Overrun::Error).is_ok());
+    }
+
+    #[test]
+    fn wide_sides_widen_the_numbers() {
+        let spec = DeckSpec {
+            cards: 3,
+            winning: 60,
+            own: 70,
+            ..DeckSpec::default()
+        };
+        let deck = read_deck(&generate(&spec), ParseMode::Strict).unwrap();
+        assert!(deck.iter().all(|card| card.winning.len() == 60));
+    }
+
+    #[test]
+    fn win_probability_bounds() {
+        let never = read_deck(&generate(&spec(100, 0.0, 1)), ParseMode::Strict).unwrap();
+        assert!(never.iter().all(|card| card.matches() == 0));
+        let always = read_deck(&generate(&spec(100, 1.0, 1)), ParseMode::Strict).unwrap();
+        let matches: Vec<usize> = always.iter().map(|card| card.matches()).collect();
+        assert_eq!(matches[..90], [10; 90]);
+        assert_eq!(matches[90..], [9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn part_1_scores_generated_decks() {
+        for seed in 0..5 {
+            let deck = read_deck(&generate(&spec(300, 0.1, seed)), ParseMode::Strict).unwrap();
+            let points: u64 = deck
+                .iter()
+                .map(|card| match card.matches() {
+                    0 => 0,
+                    n => 2u64.pow(n as u32 - 1),
+                })
+                .sum();
+            assert_eq!(solve_part_1(&deck).unwrap(), points, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn wide_decks_report_overflows() {
+        // 80 matches are worth 2^79 points and the copies double along the deck
+        let spec = DeckSpec {
+            cards: 100,
+            winning: 80,
+            own: 80,
+            win_probability: 1.0,
+            seed: 3,
+        };
+        let deck = read_deck(&generate(&spec), ParseMode::Strict).unwrap();
+        assert_eq!(
+            solve_part_1(&deck).unwrap_err().to_string(),
+            "card 1 has 80 matches, worth more points than fit a 64-bit count"
+        );
+        assert!(solve_part_2(&deck).is_err());
+    }
+
+    #[test]
+    fn cascade_matches_the_backwards_count() {
+        for seed in 0..20 {
+            let deck = read_deck(&generate(&spec(300, 0.1, seed)), ParseMode::Strict).unwrap();
+            let expected = backwards_count(&deck);
+            assert_eq!(
+                count_cards(&deck, Overrun::Error).unwrap(),
+                expected,
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
@@ -1,12 +1,9 @@
+use std::collections::HashSet;
 
-
-use std::collections::{HashSet};
-
-
-use std::path::Path;
-use std::{fs};
-
-const DAY: i8 = 4;
+use crate::debug;
+use crate::error::{Error, Result};
+use crate::scan;
+use crate::solution::Solution;
 
 const DUMMY_INPUT_P1: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
@@ -15,100 +12,410 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-// create a type alias for the matrix
-
-fn read_input(s: &str) -> Vec<(HashSet<i32>, HashSet<i32>)> {
-    return s
-        .lines()
-        .into_iter()
-        .map(|line| line.split_once("|").unwrap())
-        .map(|(winning_numbers_s, my_numbers_s)| {
-            (
-                winning_numbers_s
-                    .split(" ")
-                    .filter_map(|x| x.parse().ok())
-                    .collect(),
-                my_numbers_s
-                    .split(" ")
-                    .filter_map(|x| x.parse().ok())
-                    .collect(),
-            )
-        })
-        .collect();
+pub mod explain;
+pub mod generate;
+
+#[derive(Debug)]
+pub struct Scratchcard {
+    pub id: u32,
+    pub winning: HashSet<i32>,
+    pub mine: HashSet<i32>,
 }
 
-fn solve_part_1(input: &str) -> i32 {
-    let deck_extractions = read_input(input);
-    let my_winning_numbers: Vec<HashSet<i32>> = deck_extractions
-        .iter()
-        .map(|(winning_cards, my_cards)| winning_cards.intersection(my_cards).cloned().collect())
-        .collect();
+impl Scratchcard {
+    /// My numbers that are also winning numbers, in ascending order.
+    pub fn matching(&self) -> Vec<i32> {
+        let mut numbers: Vec<i32> = self.winning.intersection(&self.mine).cloned().collect();
+        numbers.sort();
+        return numbers;
+    }
 
-    return my_winning_numbers
-        .iter()
-        .map(|nums| match nums.len() {
-            0 => 0,
-            _ => 2i32.pow((nums.len() - 1).try_into().unwrap()),
-        })
-        .sum();
+    pub fn matches(&self) -> usize {
+        return self.winning.intersection(&self.mine).count();
+    }
+
+    // the first match is worth one point, every further match doubles it; None past 64 matches
+    pub fn points(&self) -> Option<u64> {
+        return match self.matches() {
+            0 => Some(0),
+            n => 1u64.checked_shl(n as u32 - 1),
+        };
+    }
 }
 
-fn solve_part_2(input: &str) -> i32 {
-    let deck_extractions = read_input(input);
-    let my_winning_numbers_count: Vec<i32> = deck_extractions
+pub type Deck = Vec<Scratchcard>;
+
+/// How forgiving `read_deck` is with malformed cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Take every whitespace-separated field that is a number, skip the ones that are not.
+    #[default]
+    Lenient,
+    /// Reject non-numeric tokens, numbers listed twice on a side, cards whose sides differ in
+    /// length from the first card and ids that do not count up from 1.
+    Strict,
+}
+
+// one side of a card, `side` starts at byte `start` of `line`
+fn read_side(
+    line: &str,
+    line_num: usize,
+    side: &str,
+    start: usize,
+    side_name: &str,
+    mode: ParseMode,
+) -> Result<HashSet<i32>> {
+    let index = line[..start].chars().count();
+    let mut numbers = HashSet::new();
+    for token in scan::fields(side).map(|token| token.offset_by(start, index)) {
+        let number = match mode {
+            ParseMode::Lenient => match token.text.parse::<i32>() {
+                Ok(number) => number,
+                Err(_) => continue,
+            },
+            ParseMode::Strict => token.parse::<i32>(line_num)?,
+        };
+        if !numbers.insert(number) && mode == ParseMode::Strict {
+            return Err(Error::parse_at(
+                line_num,
+                token.index + 1,
+                format!("{} is listed twice among the {} numbers", number, side_name),
+            ));
+        }
+    }
+    return Ok(numbers);
+}
+
+// "Card N:" then the winning numbers, a '|' and my numbers
+fn read_card(line: &str, line_num: usize, mode: ParseMode) -> Result<Scratchcard> {
+    let (label, numbers) = line.split_once(':').ok_or(Error::parse(
+        line_num,
+        "expected 'Card N:' before the numbers",
+    ))?;
+    let id = label
+        .strip_prefix("Card")
+        .and_then(|id| id.trim().parse().ok())
+        .ok_or(Error::parse(
+            line_num,
+            "expected 'Card N:' before the numbers",
+        ))?;
+    // a second '|' stays with my numbers, where strict mode rejects it
+    let (winning, mine) = numbers
+        .split_once('|')
+        .ok_or(Error::parse(line_num, "expected '|' between the two sides"))?;
+    let winning_start = label.len() + 1;
+    let mine_start = winning_start + winning.len() + 1;
+    return Ok(Scratchcard {
+        id,
+        winning: read_side(line, line_num, winning, winning_start, "winning", mode)?,
+        mine: read_side(line, line_num, mine, mine_start, "own", mode)?,
+    });
+}
+
+// strict mode compares every card with the ones read before it
+fn check_card(deck: &Deck, card: &Scratchcard, line_num: usize) -> Result<()> {
+    let expected_id = deck.last().map_or(1, |previous| previous.id + 1);
+    if card.id != expected_id {
+        return Err(Error::parse(
+            line_num,
+            format!("expected card {}, found card {}", expected_id, card.id),
+        ));
+    }
+    let Some(first) = deck.first() else {
+        return Ok(());
+    };
+    for (side_name, len, first_len) in [
+        ("winning", card.winning.len(), first.winning.len()),
+        ("own", card.mine.len(), first.mine.len()),
+    ] {
+        if len != first_len {
+            return Err(Error::parse(
+                line_num,
+                format!(
+                    "card {} has {} {} numbers, card {} has {}",
+                    card.id, len, side_name, first.id, first_len
+                ),
+            ));
+        }
+    }
+    return Ok(());
+}
+
+pub fn read_deck(s: &str, mode: ParseMode) -> Result<Deck> {
+    let mut deck = vec![];
+    for (line_num, line) in s.lines().enumerate() {
+        let card = read_card(line, line_num + 1, mode)?;
+        if mode == ParseMode::Strict {
+            check_card(&deck, &card, line_num + 1)?;
+        }
+        deck.push(card);
+    }
+    return Ok(deck);
+}
+
+pub fn read_input(s: &str) -> Result<Deck> {
+    return read_deck(s, ParseMode::Lenient);
+}
+
+/// The points of every card. Errors name the first card worth more than a `u64` holds.
+pub fn points(deck: &Deck) -> Result<Vec<u64>> {
+    return deck
         .iter()
-        .map(|(winning_cards, my_cards)| winning_cards.intersection(my_cards).count() as i32)
+        .map(|card| {
+            card.points().ok_or(Error::Query(format!(
+                "card {} has {} matches, worth more points than fit a 64-bit count",
+                card.id,
+                card.matches()
+            )))
+        })
         .collect();
+}
 
-    let num_cards = my_winning_numbers_count.len();
+pub fn solve_part_1(deck: &Deck) -> Result<u64> {
+    return points(deck)?.iter().try_fold(0u64, |total, n| {
+        total.checked_add(*n).ok_or(Error::Query(
+            "total points overflow a 64-bit count".to_string(),
+        ))
+    });
+}
 
-    let mut my_card_copies: Vec<i32> = (0..num_cards).map(|_| 1).collect();
+/// What to do when a card wins copies of cards past the end of the deck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overrun {
+    /// Win only the cards that exist, as the puzzle states never happens anyway.
+    #[default]
+    Clamp,
+    /// Report the card as an error.
+    Error,
+}
 
-    (0..num_cards).for_each(|i| {
-        let winning_numbers_count = my_winning_numbers_count[i];
-        let card_copies = my_card_copies[i];
+/// How many copies of every card end up in hand. Errors name the card that overran the deck
+/// or whose copies no longer fit in a `u64`.
+pub fn cascade(deck: &Deck, overrun: Overrun) -> Result<Vec<u64>> {
+    let mut copies: Vec<u64> = vec![1; deck.len()];
+    for (i, card) in deck.iter().enumerate() {
+        let wins = card.matches();
+        let remaining = deck.len() - i - 1;
+        if wins > remaining && overrun == Overrun::Error {
+            return Err(Error::Query(format!(
+                "card {} wins {} cards but only {} follow it",
+                card.id, wins, remaining
+            )));
+        }
+        if wins > remaining {
+            debug!(
+                "card {} wins {} cards but only {} follow it, ignoring the rest",
+                card.id, wins, remaining
+            );
+        }
+        let card_copies = copies[i];
+        for won in &mut copies[i + 1..i + 1 + wins.min(remaining)] {
+            *won = won.checked_add(card_copies).ok_or(Error::Query(format!(
+                "copies won by card {} overflow a 64-bit count",
+                card.id
+            )))?;
+        }
+    }
+    return Ok(copies);
+}
 
-        my_card_copies[i + 1..i + 1 + (winning_numbers_count as usize)]
-            .iter_mut()
-            .for_each(|x| *x += card_copies);
+pub fn count_cards(deck: &Deck, overrun: Overrun) -> Result<u64> {
+    let copies = cascade(deck, overrun)?;
+    return copies.iter().try_fold(0u64, |total, n| {
+        total.checked_add(*n).ok_or(Error::Query(
+            "total card count overflows a 64-bit count".to_string(),
+        ))
     });
+}
 
-    return my_card_copies.iter().sum();
+pub fn solve_part_2(deck: &Deck) -> Result<u64> {
+    return count_cards(deck, Overrun::Clamp);
 }
 
-fn gather_input_and_solve_p1() {
-    let res = solve_part_1(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
-    // read a string from file input.txt
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
+pub struct Day4;
 
-    let res = solve_part_1(input.as_str());
-    println!("Result: {}", res);
-}
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Scratchcards";
+    const EXAMPLE: &'static str = DUMMY_INPUT_P1;
 
-fn gather_input_and_solve_p2() {
-    let res = solve_part_2(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
-    // read a string from file input.txt
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
+    type Input<'a> = Deck;
+    type Answer = u64;
 
-    let res = solve_part_2(input.as_str());
-    println!("Result: {}", res);
+    fn parse(input: &str) -> Result<Deck> {
+        return read_input(input);
+    }
+    fn part1(input: &Deck) -> Result<u64> {
+        return solve_part_1(input);
+    }
+    fn part2(input: &Deck) -> Result<u64> {
+        return solve_part_2(input);
+    }
 }
 
-// Define a function to solve the code advent problem
-pub fn solve() {
-    gather_input_and_solve_p1();
-    gather_input_and_solve_p2();
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day4::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day4::part1(&input).unwrap(), 13);
+        assert_eq!(Day4::part2(&input).unwrap(), 30);
+    }
+
+    #[test]
+    fn real_input() {
+        let input = Day4::parse(include_str!("../../resources/d4/input.txt")).unwrap();
+        assert_eq!(Day4::part1(&input).unwrap(), 23673);
+        assert_eq!(Day4::part2(&input).unwrap(), 12263631);
+    }
+
+    #[test]
+    fn read_input_splits_the_two_sides() {
+        let deck = read_input("Card 12: 41 48 | 83  6 41").unwrap();
+        assert_eq!(deck.len(), 1);
+        assert_eq!(deck[0].id, 12);
+        assert_eq!(deck[0].winning, HashSet::from([41, 48]));
+        assert_eq!(deck[0].mine, HashSet::from([83, 6, 41]));
+    }
+
+    #[test]
+    fn card_matches_and_points() {
+        let deck = read_input(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(deck[0].matching(), vec![17, 48, 83, 86]);
+        assert_eq!(points(&deck).unwrap(), vec![8, 2, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn missing_card_label_is_reported() {
+        let err = read_input("41 48 | 83").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: expected 'Card N:' before the numbers"
+        );
+    }
+
+    #[test]
+    fn missing_separator_is_reported() {
+        let err = read_input("Card 1: 41 48 | 83\nCard 2: 13 32 61").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: expected '|' between the two sides"
+        );
+    }
+
+    #[test]
+    fn strict_mode_accepts_the_puzzle_inputs() {
+        assert_eq!(
+            read_deck(DUMMY_INPUT_P1, ParseMode::Strict).unwrap().len(),
+            6
+        );
+        let input = include_str!("../../resources/d4/input.txt");
+        assert_eq!(read_deck(input, ParseMode::Strict).unwrap().len(), 211);
+    }
+
+    #[test]
+    fn strict_mode_rejects_malformed_cards() {
+        let error = |s: &str| read_deck(s, ParseMode::Strict).unwrap_err().to_string();
+        assert_eq!(
+            error("Card 1: 41 4x | 83"),
+            "line 1, column 12: expected a number, found '4x'"
+        );
+        assert_eq!(
+            error("Card 1: 41 48 | 83 41 83"),
+            "line 1, column 23: 83 is listed twice among the own numbers"
+        );
+        assert_eq!(
+            error("Card 1: 41 48 | 83\nCard 2: 13 32 61"),
+            "line 2: expected '|' between the two sides"
+        );
+        assert_eq!(
+            error("Card 1: 41 48 | 83\nCard 3: 13 32 | 61"),
+            "line 2: expected card 2, found card 3"
+        );
+        assert_eq!(
+            error("Card 1: 41 48 | 83\nCard 2: 13 | 61"),
+            "line 2: card 2 has 1 winning numbers, card 1 has 2"
+        );
+    }
+
+    #[test]
+    fn numbers_against_the_bar_are_read() {
+        for mode in [ParseMode::Lenient, ParseMode::Strict] {
+            let deck = read_deck("Card 1: 41 48 |48 86\nCard 2: 13 32| 61 30", mode).unwrap();
+            assert_eq!(deck[0].winning, HashSet::from([41, 48]));
+            assert_eq!(deck[0].mine, HashSet::from([48, 86]));
+            assert_eq!(deck[1].winning, HashSet::from([13, 32]));
+            assert_eq!(deck[1].mine, HashSet::from([61, 30]));
+        }
+        let err = read_deck("Card 1: 41 | 48 |86", ParseMode::Strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 17: expected a number, found '|86'"
+        );
+        let deck = read_input("Card 1: 41 | 48 |86").unwrap();
+        assert_eq!(deck[0].mine, HashSet::from([48]));
+    }
+
+    #[test]
+    fn lenient_mode_skips_what_strict_mode_rejects() {
+        let deck = read_input("Card 3: 41 4x 41 | 83 99999999999").unwrap();
+        assert_eq!(deck[0].winning, HashSet::from([41]));
+        assert_eq!(deck[0].mine, HashSet::from([83]));
+    }
+
+    // card i matches its first `wins(i)` numbers, the rest of each side never matches
+    fn generated_deck(len: usize, wins: impl Fn(usize) -> usize) -> Deck {
+        return (0..len)
+            .map(|i| Scratchcard {
+                id: i as u32 + 1,
+                winning: (0..8).collect(),
+                mine: (0..wins(i) as i32).chain([100]).collect(),
+            })
+            .collect();
+    }
+
+    #[test]
+    fn large_deck_counts_past_i32() {
+        // every card wins the next one, so card i ends up with i copies
+        let deck = generated_deck(100_000, |_| 1);
+        assert_eq!(count_cards(&deck, Overrun::Clamp).unwrap(), 5_000_050_000);
+    }
+
+    #[test]
+    fn overrun_can_be_reported() {
+        let deck = generated_deck(100_000, |i| if i < 99_990 { 1 } else { 7 });
+        let err = count_cards(&deck, Overrun::Error).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "card 99994 wins 7 cards but only 6 follow it"
+        );
+    }
+
+    #[test]
+    fn points_overflow_is_reported() {
+        let card = |id: u32, matches: i32| Scratchcard {
+            id,
+            winning: (0..matches).collect(),
+            mine: (0..matches).collect(),
+        };
+        let deck = vec![card(1, 64), card(2, 3)];
+        assert_eq!(solve_part_1(&deck).unwrap(), (1 << 63) + 4);
+        let deck = vec![card(1, 2), card(2, 65)];
+        assert_eq!(
+            solve_part_1(&deck).unwrap_err().to_string(),
+            "card 2 has 65 matches, worth more points than fit a 64-bit count"
+        );
+        let deck = vec![card(1, 64), card(2, 64)];
+        assert!(solve_part_1(&deck).is_err());
+    }
 
-// Define the main function to run the code advent solution
-fn main() {
-    solve();
+    #[test]
+    fn overflow_is_reported() {
+        // winning two cards each makes the copies grow like the Fibonacci numbers
+        let deck = generated_deck(100_000, |_| 2);
+        let err = count_cards(&deck, Overrun::Clamp).unwrap_err();
+        assert!(err.to_string().ends_with("overflow a 64-bit count"));
+    }
 }
@@ -0,0 +1,248 @@
+use std::fmt;
+
+use super::mapping::{compose, Mapping};
+use super::{read_sections, MapFilter};
+use crate::debug;
+use crate::error::{Error, Result};
+use crate::interval::{Interval, IntervalSet};
+
+/// A kind of thing the almanac numbers, such as `seed` or `soil`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Category<'a>(pub &'a str);
+
+impl fmt::Display for Category<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.pad(self.0);
+    }
+}
+
+/// One "a-to-b map:" section.
+pub struct AlmanacMap<'a> {
+    pub source: Category<'a>,
+    pub target: Category<'a>,
+    /// Line of the section header.
+    pub line: usize,
+    pub filters: Vec<MapFilter>,
+}
+
+/// Two ranges of one map that claim some of the same numbers. The one listed first wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap<'a> {
+    pub source: Category<'a>,
+    pub target: Category<'a>,
+    pub first: Interval,
+    pub second: Interval,
+    pub common: Interval,
+}
+
+/// The seeds and the chain of maps, each map starting from the category the previous one
+/// ends at.
+pub struct Almanac<'a> {
+    pub seeds: Vec<i64>,
+    pub maps: Vec<AlmanacMap<'a>>,
+}
+
+impl<'a> Almanac<'a> {
+    pub fn parse(s: &'a str) -> Result<Almanac<'a>> {
+        let (seeds, sections) = read_sections(s)?;
+        let mut maps: Vec<AlmanacMap> = vec![];
+        for (name, line, ranges) in sections {
+            let (source, target) = name.split_once("-to-").ok_or(Error::parse(
+                line,
+                format!("expected a map name like 'seed-to-soil', found '{}'", name),
+            ))?;
+            let (source, target) = (Category(source), Category(target));
+            if let Some(previous) = maps.last() {
+                if previous.target != source {
+                    return Err(Error::parse(
+                        line,
+                        format!(
+                            "map '{}' should start from '{}', where the previous map ends",
+                            name, previous.target
+                        ),
+                    ));
+                }
+            }
+            let seen = maps.first().map(|first| first.source).into_iter();
+            if seen
+                .chain(maps.iter().map(|map| map.target))
+                .any(|category| category == target)
+            {
+                return Err(Error::parse(
+                    line,
+                    format!("category '{}' appears twice in the chain", target),
+                ));
+            }
+            let filters = ranges
+                .iter()
+                .map(|(dest_start, source_start, len)| MapFilter {
+                    source_range: Interval::with_len(*source_start, *len),
+                    dest_range: Interval::with_len(*dest_start, *len),
+                })
+                .collect();
+            maps.push(AlmanacMap {
+                source,
+                target,
+                line,
+                filters,
+            });
+        }
+        return Ok(Almanac { seeds, maps });
+    }
+
+    /// Every category in chain order, `seed` first in the puzzle input.
+    pub fn categories(&self) -> Vec<Category<'a>> {
+        let first = self.maps.first().map(|map| map.source);
+        return first
+            .into_iter()
+            .chain(self.maps.iter().map(|map| map.target))
+            .collect();
+    }
+
+    /// The seed ranges of part two, read from the seeds as (start, length) pairs. An odd
+    /// number of seeds leaves the last one without a length and is a query error.
+    pub fn seed_ranges(&self) -> Result<IntervalSet> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(Error::Query(format!(
+                "part two reads the seeds as start and length pairs, {} has no length",
+                self.seeds[self.seeds.len() - 1]
+            )));
+        }
+        return Ok(self
+            .seeds
+            .chunks_exact(2)
+            .map(|pair| Interval::with_len(pair[0], pair[1]))
+            .collect());
+    }
+
+    /// The maps leading from `from` to `to`, None unless `from` comes before `to` in the chain.
+    /// A category leads to itself through no maps.
+    pub fn chain(&self, from: &str, to: &str) -> Option<&[AlmanacMap<'a>]> {
+        let categories = self.categories();
+        let start = categories.iter().position(|c| c.0 == from)?;
+        let end = categories.iter().position(|c| c.0 == to)?;
+        return (start <= end).then(|| &self.maps[start..end]);
+    }
+
+    pub fn mapping(&self, from: &str, to: &str) -> Option<Mapping> {
+        let chain = self.chain(from, to)?;
+        debug!("composing {} maps from '{}' to '{}'", chain.len(), from, to);
+        return Some(compose(chain));
+    }
+
+    /// Like `mapping`, with a query error naming the categories when no chain links them.
+    pub fn require_mapping(&self, from: &str, to: &str) -> Result<Mapping> {
+        return self.mapping(from, to).ok_or(Error::Query(format!(
+            "the maps do not lead from '{}' to '{}'",
+            from, to
+        )));
+    }
+
+    pub fn lookup(&self, from: &str, to: &str, number: i64) -> Option<i64> {
+        return Some(self.mapping(from, to)?.apply(number));
+    }
+
+    /// Source ranges that overlap inside a map, in map order.
+    pub fn overlaps(&self) -> Vec<Overlap<'a>> {
+        let mut overlaps = vec![];
+        for map in &self.maps {
+            for (i, first) in map.filters.iter().enumerate() {
+                for second in &map.filters[i + 1..] {
+                    if let Some(common) = first.source_range.intersection(&second.source_range) {
+                        overlaps.push(Overlap {
+                            source: map.source,
+                            target: map.target,
+                            first: first.source_range,
+                            second: second.source_range,
+                            common,
+                        });
+                    }
+                }
+            }
+        }
+        return overlaps;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{find_seed_position, DUMMY_INPUT_P1};
+    use super::*;
+
+    #[test]
+    fn chain_of_categories() {
+        let almanac = Almanac::parse(DUMMY_INPUT_P1).unwrap();
+        let names: Vec<&str> = almanac.categories().iter().map(|c| c.0).collect();
+        assert_eq!(
+            names,
+            vec![
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ]
+        );
+        assert_eq!(almanac.maps[1].line, 7);
+        assert_eq!(almanac.seed_ranges().unwrap().len(), 27);
+    }
+
+    #[test]
+    fn lookups_between_any_two_categories() {
+        let almanac = Almanac::parse(DUMMY_INPUT_P1).unwrap();
+        // seed 79 has soil 81, fertilizer 81, water 81, light 74, temperature 78,
+        // humidity 78 and location 82
+        assert_eq!(almanac.lookup("soil", "humidity", 81), Some(78));
+        assert_eq!(almanac.lookup("water", "light", 81), Some(74));
+        assert_eq!(almanac.lookup("soil", "soil", 81), Some(81));
+        assert_eq!(almanac.lookup("humidity", "soil", 78), None);
+        assert_eq!(almanac.lookup("seed", "dirt", 1), None);
+
+        for seed in 0..110 {
+            assert_eq!(
+                almanac.lookup("seed", "location", seed),
+                Some(find_seed_position(seed, &almanac.maps))
+            );
+        }
+    }
+
+    #[test]
+    fn broken_chains_are_reported() {
+        let error = |s: &str| Almanac::parse(s).err().unwrap().to_string();
+        assert_eq!(
+            error("seeds: 1\n\nseed-to-soil map:\n1 2 3\n\nwater-to-light map:\n1 2 3"),
+            "line 6: map 'water-to-light' should start from 'soil', where the previous map ends"
+        );
+        assert_eq!(
+            error("seeds: 1\n\nseed-to-soil map:\n\nsoil-to-seed map:\n"),
+            "line 5: category 'seed' appears twice in the chain"
+        );
+        assert_eq!(
+            error("seeds: 1\n\nseed-of-soil map:\n"),
+            "line 3: expected a map name like 'seed-to-soil', found 'seed-of-soil'"
+        );
+    }
+
+    #[test]
+    fn overlapping_ranges_are_reported() {
+        let almanac =
+            Almanac::parse("seeds: 1\n\nseed-to-soil map:\n50 98 2\n10 90 10\n0 0 5").unwrap();
+        assert_eq!(
+            almanac.overlaps(),
+            vec![Overlap {
+                source: Category("seed"),
+                target: Category("soil"),
+                first: Interval::new(98, 100),
+                second: Interval::new(90, 100),
+                common: Interval::new(98, 100),
+            }]
+        );
+        assert!(Almanac::parse(DUMMY_INPUT_P1)
+            .unwrap()
+            .overlaps()
+            .is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+use std::fmt::Write;
+
+use super::almanac::AlmanacMap;
+use super::MapFilter;
+use crate::interval::{Interval, IntervalSet};
+use crate::{debug, trace};
+
+/// Numbers in `domain` move by `delta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub domain: Interval,
+    pub delta: i64,
+}
+
+/// A piecewise-linear function on all integers: every piece moves its domain by a constant,
+/// numbers outside every piece map to themselves. Pieces are sorted, disjoint and never zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mapping {
+    pieces: Vec<Piece>,
+}
+
+impl Mapping {
+    pub fn identity() -> Mapping {
+        return Mapping::default();
+    }
+
+    // sorts the pieces and merges neighbours that move by the same amount
+    fn from_pieces(mut pieces: Vec<Piece>) -> Mapping {
+        pieces.retain(|piece| piece.delta != 0 && !piece.domain.is_empty());
+        pieces.sort_by_key(|piece| piece.domain.start);
+        let mut merged: Vec<Piece> = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            match merged.last_mut() {
+                Some(last)
+                    if last.domain.end == piece.domain.start && last.delta == piece.delta =>
+                {
+                    last.domain.end = piece.domain.end
+                }
+                _ => merged.push(piece),
+            }
+        }
+        return Mapping { pieces: merged };
+    }
+
+    /// One almanac map. Where source ranges overlap the filter listed first wins, as in
+    /// `find_seed_position`.
+    pub fn from_filters(filters: &[MapFilter]) -> Mapping {
+        let mut covered = IntervalSet::new();
+        let mut pieces = vec![];
+        for filter in filters {
+            let fresh = IntervalSet::from(filter.source_range).difference(&covered);
+            pieces.extend(fresh.intervals().iter().map(|domain| Piece {
+                domain: *domain,
+                delta: filter.delta(),
+            }));
+            covered.insert(filter.source_range);
+        }
+        return Mapping::from_pieces(pieces);
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        return &self.pieces;
+    }
+
+    fn piece_at(&self, x: i64) -> Option<&Piece> {
+        let i = self.pieces.partition_point(|piece| piece.domain.end <= x);
+        return self.pieces.get(i).filter(|piece| piece.domain.contains(x));
+    }
+
+    pub fn apply(&self, x: i64) -> i64 {
+        return x + self.piece_at(x).map_or(0, |piece| piece.delta);
+    }
+
+    /// Where the mapping changes how far it moves numbers, in ascending order.
+    pub fn breakpoints(&self) -> Vec<i64> {
+        let mut points: Vec<i64> = self
+            .pieces
+            .iter()
+            .flat_map(|piece| [piece.domain.start, piece.domain.end])
+            .collect();
+        points.dedup();
+        return points;
+    }
+
+    /// Splits `range` where the mapping's pieces begin and end, each part with its delta.
+    fn segments(&self, range: Interval) -> Vec<Piece> {
+        let mut segments = vec![];
+        let mut start = range.start;
+        let first = self
+            .pieces
+            .partition_point(|piece| piece.domain.end <= start);
+        for piece in &self.pieces[first..] {
+            if start >= range.end || piece.domain.start >= range.end {
+                break;
+            }
+            if start < piece.domain.start {
+                segments.push(Piece {
+                    domain: Interval::new(start, piece.domain.start),
+                    delta: 0,
+                });
+                start = piece.domain.start;
+            }
+            let end = piece.domain.end.min(range.end);
+            segments.push(Piece {
+                domain: Interval::new(start, end),
+                delta: piece.delta,
+            });
+            start = end;
+        }
+        if start < range.end {
+            segments.push(Piece {
+                domain: Interval::new(start, range.end),
+                delta: 0,
+            });
+        }
+        return segments;
+    }
+
+    pub fn apply_range(&self, range: Interval) -> IntervalSet {
+        return self
+            .segments(range)
+            .iter()
+            .map(|segment| segment.domain.shift(segment.delta))
+            .collect();
+    }
+
+    pub fn apply_set(&self, set: &IntervalSet) -> IntervalSet {
+        return set
+            .intervals()
+            .iter()
+            .flat_map(|range| self.segments(*range))
+            .map(|segment| segment.domain.shift(segment.delta))
+            .collect();
+    }
+
+    /// Every number the mapping sends into `set`. Several pieces may land on the same numbers,
+    /// so one range can come from many.
+    pub fn preimage(&self, set: &IntervalSet) -> IntervalSet {
+        let domains: IntervalSet = self.pieces.iter().map(|piece| piece.domain).collect();
+        // numbers outside every piece map to themselves
+        let mut preimage = set.difference(&domains);
+        for piece in &self.pieces {
+            let from = set.shift(-piece.delta).intersection_with(piece.domain);
+            preimage = preimage.union(&from);
+        }
+        return preimage;
+    }
+
+    /// The mapping that applies `self` and then `next`.
+    pub fn then(&self, next: &Mapping) -> Mapping {
+        // the composition can only change where `self` does, or where `self` lands on a
+        // breakpoint of `next`
+        let mut cuts = self.breakpoints();
+        for b in next.breakpoints() {
+            if self.piece_at(b).is_none() {
+                cuts.push(b);
+            }
+            for piece in &self.pieces {
+                if piece.domain.contains(b - piece.delta) {
+                    cuts.push(b - piece.delta);
+                }
+            }
+        }
+        cuts.sort();
+        cuts.dedup();
+        let pieces = cuts
+            .windows(2)
+            .map(|w| Piece {
+                domain: Interval::new(w[0], w[1]),
+                delta: next.apply(self.apply(w[0])) - w[0],
+            })
+            .collect();
+        return Mapping::from_pieces(pieces);
+    }
+
+    /// One line per piece, for inspecting a composed chain.
+    pub fn dump(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "{:>12}  {:>12}  {:>12}", "Start", "End", "Delta").unwrap();
+        for piece in &self.pieces {
+            writeln!(
+                out,
+                "{:>12}  {:>12}  {:>+12}",
+                piece.domain.start, piece.domain.end, piece.delta
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "{} pieces, any other number maps to itself",
+            self.pieces.len()
+        )
+        .unwrap();
+        return out;
+    }
+}
+
+/// A chain of maps, such as `seed-to-soil` through `humidity-to-location`, as one mapping.
+pub fn compose(maps: &[AlmanacMap]) -> Mapping {
+    return maps.iter().fold(Mapping::identity(), |mapping, map| {
+        let composed = mapping.then(&Mapping::from_filters(&map.filters));
+        debug!(
+            "{}-to-{}: {} ranges, {} pieces so far",
+            map.source,
+            map.target,
+            map.filters.len(),
+            composed.pieces().len()
+        );
+        trace!("pieces={:?}", composed.pieces());
+        return composed;
+    });
+}
+
+/// Every number at the start of `maps` that ends up in `set`, walking the maps back from the
+/// last to the first.
+pub fn preimage_through(maps: &[AlmanacMap], set: &IntervalSet) -> IntervalSet {
+    return maps.iter().rev().fold(set.clone(), |set, map| {
+        Mapping::from_filters(&map.filters).preimage(&set)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::almanac::Almanac;
+    use super::super::{find_seed_position, DUMMY_INPUT_P1};
+    use super::*;
+
+    fn filter(dest: i64, source: i64, len: i64) -> MapFilter {
+        return MapFilter {
+            source_range: Interval::with_len(source, len),
+            dest_range: Interval::with_len(dest, len),
+        };
+    }
+
+    fn bounds(set: &IntervalSet) -> Vec<(i64, i64)> {
+        return set.intervals().iter().map(|i| (i.start, i.end)).collect();
+    }
+
+    #[test]
+    fn single_map() {
+        let mapping = Mapping::from_filters(&[filter(50, 98, 2), filter(52, 50, 48)]);
+        assert_eq!(mapping.breakpoints(), vec![50, 98, 100]);
+        assert_eq!(
+            [0, 49, 50, 79, 97, 98, 99, 100].map(|x| mapping.apply(x)),
+            [0, 49, 52, 81, 99, 50, 51, 100]
+        );
+        assert_eq!(
+            bounds(&mapping.apply_range(Interval::new(40, 105))),
+            vec![(40, 105)]
+        );
+        assert_eq!(
+            bounds(&mapping.apply_range(Interval::new(96, 100))),
+            vec![(50, 52), (98, 100)]
+        );
+    }
+
+    #[test]
+    fn first_filter_wins_on_overlap() {
+        let mapping = Mapping::from_filters(&[filter(100, 10, 10), filter(200, 15, 10)]);
+        assert_eq!(mapping.apply(17), 107);
+        assert_eq!(mapping.apply(22), 207);
+    }
+
+    #[test]
+    fn composition_applies_in_order() {
+        let first = Mapping::from_filters(&[filter(20, 0, 10)]);
+        let second = Mapping::from_filters(&[filter(100, 25, 10)]);
+        let both = first.then(&second);
+        for x in -5..50 {
+            assert_eq!(both.apply(x), second.apply(first.apply(x)), "x = {}", x);
+        }
+    }
+
+    #[test]
+    fn composed_chain_matches_walking_the_maps() {
+        let almanac = Almanac::parse(DUMMY_INPUT_P1).unwrap();
+        let mapping = compose(&almanac.maps);
+        for x in (0..120).chain(almanac.seeds.iter().copied()) {
+            assert_eq!(
+                mapping.apply(x),
+                find_seed_position(x, &almanac.maps),
+                "x = {}",
+                x
+            );
+        }
+        assert_eq!(
+            mapping.apply_range(Interval::with_len(79, 14)).min(),
+            Some(46)
+        );
+    }
+
+    #[test]
+    fn dump_lists_the_pieces() {
+        let dump = Mapping::from_filters(&[filter(50, 98, 2)]).dump();
+        assert_eq!(
+            dump,
+            "       Start           End         Delta\n          98           100           -48\n1 pieces, any other number maps to itself\n"
+        );
+    }
+
+    #[test]
+    fn preimage_collects_every_source() {
+        // 10..15 lands on 100..105, as does 103..105 which no piece moves
+        let mapping = Mapping::from_filters(&[filter(100, 10, 10), filter(0, 100, 3)]);
+        let preimage = mapping.preimage(&Interval::new(100, 105).into());
+        assert_eq!(bounds(&preimage), vec![(10, 15), (103, 105)]);
+        let preimage = mapping.preimage(&Interval::new(0, 2).into());
+        assert_eq!(bounds(&preimage), vec![(0, 2), (100, 102)]);
+    }
+
+    #[test]
+    fn preimage_round_trips_through_the_chain() {
+        let almanac = Almanac::parse(DUMMY_INPUT_P1).unwrap();
+        let mapping = compose(&almanac.maps);
+        let locations = IntervalSet::from(Interval::new(40, 60));
+        let seeds = preimage_through(&almanac.maps, &locations);
+        for x in -10..150 {
+            assert_eq!(
+                seeds.contains(x),
+                locations.contains(mapping.apply(x)),
+                "x = {}",
+                x
+            );
+        }
+        assert_eq!(mapping.preimage(&locations), seeds);
+        // the example's lowest location, 46, comes from seed 82 among others
+        assert!(preimage_through(&almanac.maps, &Interval::with_len(46, 1).into()).contains(82));
+    }
+}
@@ -1,15 +1,8 @@
-use regex::Regex;
-
-use std::collections::HashMap;
-use std::fmt::Display;
-use std::hash::Hash;
-use std::io::Write;
-use std::iter::zip;
-use std::ops::Deref;
-use std::path::Path;
-use std::{fs, io, vec};
-
-const DAY: i8 = 5;
+use crate::error::{Error, Result};
+use crate::interval::Interval;
+use crate::scan;
+use crate::solution::Solution;
+use crate::{debug, trace};
 
 const DUMMY_INPUT_P1: &str = "seeds: 79 14 55 13
 
@@ -44,313 +37,317 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-// write a regex to parse the input
-
-fn read_input(s: &str) -> (Vec<i64>, Vec<(&str, Vec<(i64, i64, i64)>)>) {
-    let seeds_re: Regex = Regex::new(r"(\d+)").unwrap();
-    let map_re: Regex = Regex::new(
-        r"(?:(?<map_name>\w+-\w+-\w+) map:)|(?:(?<dest_start>\d+) (?<source_start>\d+) (?<len>\d+))",
-    )
-    .unwrap();
-    let (seeds_s, maps_s) = s.split_once("\n").unwrap();
-    // let MAP_NAME_RE: Regex = Regex::new(r"^(\w+-\w+) map:").unwrap();
-    let seeds: Vec<i64> = seeds_re
-        .captures_iter(seeds_s)
-        .map(|cap| cap[0].parse::<i64>().unwrap())
-        .collect();
-    let mut maps: Vec<(&str, Vec<(i64, i64, i64)>)> = vec![];
-    map_re
-        .captures_iter(maps_s)
-        .into_iter()
-        .fold(None, |cur_map_name: Option<&str>, cap| {
-            let map_name = cap.name("map_name");
-            if map_name.is_some() {
-                maps.push((map_name.unwrap().as_str(), vec![]));
-                return Some(map_name.unwrap().as_str());
-            }
-            maps.last_mut().unwrap().1.push((
-                cap.name("dest_start")
-                    .unwrap()
-                    .as_str()
-                    .parse::<i64>()
-                    .unwrap(),
-                cap.name("source_start")
-                    .unwrap()
-                    .as_str()
-                    .parse::<i64>()
-                    .unwrap(),
-                cap.name("len").unwrap().as_str().parse::<i64>().unwrap(),
-            ));
-            return cur_map_name;
-        });
-    return (seeds, maps);
-}
+pub mod almanac;
+pub mod mapping;
 
-fn find_seed_position(seed: i64, maps: &Vec<(&str, Vec<(i64, i64, i64)>)>) -> i64 {
-    return maps.iter().fold(seed, |seed_position: i64, map| {
-        map.1
-            .iter()
-            .find_map(|(dest_start, source_start, len)| {
-                if *source_start <= seed_position && seed_position < *source_start + *len {
-                    return Some(*dest_start + (seed_position - *source_start));
-                }
-                return None;
-            })
-            .unwrap_or(seed_position)
-    });
-}
+use almanac::{Almanac, AlmanacMap};
 
-#[derive(Debug, Clone, Copy)]
-struct SeedRange {
-    start: i64,
-    end: i64,
-}
+pub const SEED: &str = "seed";
+pub const LOCATION: &str = "location";
 
-#[derive(Debug, Clone, Copy)]
-struct SeedRangeNextPositions {
-    seeds_to_elaborate: Option<SeedRange>,
-    next_positions: Option<SeedRange>,
+// "seed-to-soil" and the like: three words joined by dashes
+fn is_map_name(name: &str) -> bool {
+    let words: Vec<&str> = scan::words(name).map(|token| token.text).collect();
+    return words.len() == 3 && words.join("-") == name;
 }
 
-#[derive(Debug, Clone)]
-struct MapFilter {
-    source_range: SeedRange,
-    dest_range: SeedRange,
+// `dest_start source_start len`, or None when the line is anything else
+fn read_range(line: &str, line_num: usize) -> Result<Option<(i64, i64, i64)>> {
+    let fields: Vec<scan::Token> = scan::fields(line).collect();
+    if fields.len() != 3
+        || !fields
+            .iter()
+            .all(|field| field.text.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return Ok(None);
+    }
+    let (dest_start, source_start, len): (i64, i64, i64) = (
+        fields[0].parse(line_num)?,
+        fields[1].parse(line_num)?,
+        fields[2].parse(line_num)?,
+    );
+    if source_start.max(dest_start).checked_add(len).is_none() {
+        return Err(Error::parse_at(
+            line_num,
+            fields[2].index + 1,
+            format!("a range of {} runs past the largest 64-bit number", len),
+        ));
+    }
+    return Ok(Some((dest_start, source_start, len)));
 }
 
-#[derive(Debug, Clone)]
-struct MapState {
-    seeds_to_elaborate: Vec<SeedRange>,
-    next_positions: Vec<SeedRange>,
-}
+// every map with the line of its header
+type Sections<'a> = Vec<(&'a str, usize, Vec<(i64, i64, i64)>)>;
 
-fn elaborate_next_positions(
-    seed_range: SeedRange,
-    source_range: SeedRange, // equal length
-    dest_range: SeedRange,   // equal length
-) -> SeedRangeNextPositions {
-    let delta = dest_range.start - source_range.start;
-    // check if seed_range is not contained in source_range
-    if source_range.end <= seed_range.start || seed_range.end <= source_range.start {
-        return SeedRangeNextPositions {
-            seeds_to_elaborate: Some(seed_range),
-            next_positions: None,
-        };
-    }
-    // check if seed_range is contained in source_range
-    else if source_range.start <= seed_range.start && seed_range.end <= source_range.end {
-        return SeedRangeNextPositions {
-            seeds_to_elaborate: None,
-            next_positions: Some(SeedRange {
-                start: seed_range.start + delta,
-                end: seed_range.end + delta,
-            }),
-        };
-    }
-    // check if seed_range overlaps with left side of source_range
-    else if source_range.start <= seed_range.start && seed_range.start < source_range.end {
-        return SeedRangeNextPositions {
-            seeds_to_elaborate: Some(SeedRange {
-                // wont translate
-                start: seed_range.start,
-                end: source_range.start,
-            }),
-            next_positions: Some(SeedRange {
-                // will translate
-                start: dest_range.start,
-                end: dest_range.start + (seed_range.end - source_range.end),
-            }),
-        };
-    }
-    // check if seed_range overlaps with right side of next_position_range
-    else if {
-        source_range.start < seed_range.end
-            && seed_range.end <= source_range.end
-            && seed_range.start < source_range.end
-    } {
-        return SeedRangeNextPositions {
-            seeds_to_elaborate: Some(SeedRange {
-                // wont translate
-                start: source_range.end,
-                end: seed_range.end,
-            }),
-            next_positions: Some(SeedRange {
-                // will translate
-                start: dest_range.end - (source_range.end - seed_range.end),
-                end: dest_range.end,
-            }),
-        };
+fn read_sections(s: &str) -> Result<(Vec<i64>, Sections<'_>)> {
+    let mut lines = s.lines().enumerate();
+    let seeds_line = lines
+        .next()
+        .map(|(_, line)| line)
+        .filter(|line| line.starts_with("seeds:"))
+        .ok_or(Error::parse(
+            1,
+            "expected 'seeds:' followed by the seed numbers",
+        ))?;
+    let tokens: Vec<scan::Token> = scan::numbers(seeds_line).collect();
+    let seeds: Vec<i64> = tokens
+        .iter()
+        .map(|token| token.parse::<i64>(1))
+        .collect::<Result<_>>()?;
+    // part two reads the seeds as start and length pairs
+    for (pair, tokens) in seeds.chunks_exact(2).zip(tokens.chunks_exact(2)) {
+        if pair[0].checked_add(pair[1]).is_none() {
+            return Err(Error::parse_at(
+                1,
+                tokens[1].index + 1,
+                format!(
+                    "seed range {} {} runs past the largest 64-bit number",
+                    pair[0], pair[1]
+                ),
+            ));
+        }
     }
-    // check if seed_range overlaps with both sides of source_range
-    else if source_range.start < seed_range.start && seed_range.end < source_range.end {
-        return SeedRangeNextPositions {
-            seeds_to_elaborate: Some(SeedRange {
-                // wont translate
-                start: source_range.end,
-                end: seed_range.end,
-            }),
-            next_positions: Some(SeedRange {
-                // will translate
-                start: dest_range.end - (source_range.end - seed_range.end),
-                end: dest_range.end,
-            }),
-        };
+    if seeds.is_empty() {
+        return Err(Error::parse(1, "no seeds listed"));
     }
-    panic!("unhandled case");
-}
 
-fn translate_seed_ranges_with_filter(state: MapState, cur_filter: MapFilter) -> MapState {
-    let seeds_to_elaborate = state.seeds_to_elaborate;
-    let mut new_state = MapState {
-        seeds_to_elaborate: vec![],
-        next_positions: state.next_positions,
-    };
-    for seed_range in seeds_to_elaborate {
-        let _state = elaborate_next_positions(
-            seed_range.clone(),
-            cur_filter.source_range,
-            cur_filter.dest_range,
-        );
-        if _state.next_positions.is_some() {
-            new_state
-                .next_positions
-                .push(_state.next_positions.unwrap());
+    let mut maps: Sections = vec![];
+    for (line_num, line) in lines {
+        let line_num = line_num + 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
         }
-        if _state.seeds_to_elaborate.is_some() {
-            new_state
-                .seeds_to_elaborate
-                .push(_state.seeds_to_elaborate.unwrap());
+        if let Some(map_name) = line.strip_suffix(" map:").filter(|name| is_map_name(name)) {
+            maps.push((map_name, line_num, vec![]));
+            continue;
         }
+        let range = read_range(line, line_num)?.ok_or(Error::parse(
+            line_num,
+            "expected an 'a-to-b map:' header or three integers",
+        ))?;
+        maps.last_mut()
+            .ok_or(Error::parse(line_num, "range listed before any map header"))?
+            .2
+            .push(range);
     }
-    return new_state;
+    return Ok((seeds, maps));
 }
 
-fn find_seed_range_positions(
-    seed_range: SeedRange,
-    maps: &Vec<(&str, Vec<MapFilter>)>,
-) -> Vec<SeedRange> {
-    println!("##############################################");
-    println!("Start execution for seed_range={:?}", seed_range.clone());
-
-    let res = maps.iter().fold(
-        MapState {
-            seeds_to_elaborate: vec![seed_range],
-            next_positions: vec![],
-        }, // init with seed range
-        |map_state, cur_map| {
-            println!("|--map_state={:?}", map_state.clone());
-            println!("|--map={:?}", cur_map.clone());
-            let map_res = cur_map
-                .1
-                .iter()
-                .fold(map_state.clone(), |filter_state, cur_filter| {
-                    println!("|----filter={:?}", cur_filter.clone());
-                    let new_filter_state =
-                        translate_seed_ranges_with_filter(filter_state, cur_filter.clone());
-                    println!("|----new_filter_state={:?}", new_filter_state.clone());
-                    return new_filter_state;
-                });
-            println!("|--new_map_state={:?}", map_res.clone());
-            let mut new_seed_to_elaborate = map_res.seeds_to_elaborate.clone();
-            new_seed_to_elaborate.extend(map_res.next_positions.clone());
-            MapState {
-                seeds_to_elaborate: new_seed_to_elaborate,
-                next_positions: vec![],
-            }
-        },
-    );
-    println!("res={:?}", res.clone());
-    println!("End execution for seed_range={:?}", seed_range.clone());
-    println!("##############################################");
-    io::stdout().flush().unwrap();
-    res.seeds_to_elaborate
+/// Walks `seed` through `maps` one map at a time, where the first range containing it wins.
+pub fn find_seed_position(seed: i64, maps: &[AlmanacMap]) -> i64 {
+    return maps.iter().fold(seed, |position, map| {
+        map.filters
+            .iter()
+            .find(|filter| filter.source_range.contains(position))
+            .map_or(position, |filter| position + filter.delta())
+    });
 }
 
-fn solve_part_1(_input: &str) -> i64 {
-    let (seeds, maps) = read_input(_input);
-    return seeds
-        .iter()
-        .map(|seed| find_seed_position(*seed, &maps))
-        .min()
-        .unwrap();
+#[derive(Debug, Clone)]
+pub struct MapFilter {
+    pub source_range: Interval,
+    pub dest_range: Interval,
 }
 
-fn solve_part_2(_input: &str) -> i64 {
-    let (seeds, maps) = read_input(_input);
-    // iter over pair of seeds
-    let seed_ranges: Vec<SeedRange> = zip(
-        seeds[0..seeds.len() - 1].iter().step_by(2),
-        seeds[1..seeds.len()].iter().step_by(2),
-    )
-    .map(|(seed_pos, num_seeds)| SeedRange {
-        start: *seed_pos,
-        end: *seed_pos + *num_seeds,
-    })
-    .collect();
-
-    let maps: Vec<(&str, Vec<MapFilter>)> = maps
-        .iter()
-        .map(|(map_name, map)| {
-            (
-                *map_name,
-                map.iter()
-                    .map(|(dest_start, source_start, len)| MapFilter {
-                        source_range: SeedRange {
-                            start: *source_start,
-                            end: *source_start + *len,
-                        },
-                        dest_range: SeedRange {
-                            start: *dest_start,
-                            end: *dest_start + *len,
-                        },
-                    })
-                    .collect(),
-            )
-        })
-        .collect(); // add source range to map
-                    // iter 1 seed range over many maps
-
-    return seed_ranges
+impl MapFilter {
+    pub fn delta(&self) -> i64 {
+        return self.dest_range.start - self.source_range.start;
+    }
+}
+
+pub fn solve_part_1(almanac: &Almanac) -> Result<i64> {
+    let mapping = almanac.require_mapping(SEED, LOCATION)?;
+    return almanac
+        .seeds
         .iter()
-        .map(|seed_range| {
-            find_seed_range_positions(seed_range.clone(), &maps)
-                .iter()
-                .map(|seed_range| seed_range.start)
-                .min()
-                .unwrap()
-        })
+        .map(|seed| mapping.apply(*seed))
         .min()
-        .unwrap();
+        .ok_or(Error::Query("the almanac lists no seeds".to_string()));
 }
 
-fn gather_input_and_solve_p1() {
-    let res = solve_part_1(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
-    // read a string from file input.txt
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
-
-    let res = solve_part_1(input.as_str());
-    println!("Result: {}", res);
+pub fn solve_part_2(almanac: &Almanac) -> Result<i64> {
+    let mapping = almanac.require_mapping(SEED, LOCATION)?;
+    let seeds = almanac.seed_ranges()?;
+    let locations = mapping.apply_set(&seeds);
+    debug!(
+        "{} seed ranges land on {} location ranges",
+        seeds.intervals().len(),
+        locations.intervals().len()
+    );
+    trace!("locations={:?}", locations);
+    return locations.min().ok_or(Error::Query(
+        "every seed range is empty, there is no location to report".to_string(),
+    ));
 }
 
-fn gather_input_and_solve_p2() {
-    let res = solve_part_2(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
-    // read a string from file input.txt
-    // let path = Path::new("resources")
-    //     .join(format!("d{}", DAY))
-    //     .join("input.txt");
-    // let input = fs::read_to_string(path).expect("Something went wrong reading the file");
-
-    // let res = solve_part_2(input.as_str());
-    // println!("Result: {}", res);
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+    const EXAMPLE: &'static str = DUMMY_INPUT_P1;
+
+    type Input<'a> = Almanac<'a>;
+    type Answer = i64;
+
+    // both parts need the maps to lead from seeds to locations
+    fn parse(input: &str) -> Result<Almanac<'_>> {
+        let almanac = Almanac::parse(input)?;
+        if almanac.chain(SEED, LOCATION).is_none() {
+            let line = almanac.maps.first().map_or(1, |map| map.line);
+            return Err(Error::parse(
+                line,
+                "the maps do not lead from 'seed' to 'location'",
+            ));
+        }
+        return Ok(almanac);
+    }
+    fn part1(input: &Almanac) -> Result<i64> {
+        return solve_part_1(input);
+    }
+    fn part2(input: &Almanac) -> Result<i64> {
+        return solve_part_2(input);
+    }
 }
 
-// Define a function to solve the code advent problem
-pub fn solve() {
-    // gather_input_and_solve_p1();
-    gather_input_and_solve_p2();
+#[cfg(test)]
+mod tests {
+    use super::mapping::Mapping;
+    use super::*;
+    use crate::interval::IntervalSet;
+
+    const FILTER: MapFilter = MapFilter {
+        source_range: Interval { start: 10, end: 20 },
+        dest_range: Interval {
+            start: 100,
+            end: 110,
+        },
+    };
+
+    type Bounds = Vec<(i64, i64)>;
+
+    fn bounds(set: &IntervalSet) -> Bounds {
+        return set.intervals().iter().map(|i| (i.start, i.end)).collect();
+    }
+
+    // where the seeds in `start..end` end up after a map with just FILTER
+    fn moved(start: i64, end: i64) -> Bounds {
+        let mapping = Mapping::from_filters(&[FILTER]);
+        return bounds(&mapping.apply_range(Interval::new(start, end)));
+    }
+
+    #[test]
+    fn example_part_1() {
+        let input = Day5::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day5::part1(&input).unwrap(), 35);
+    }
+
+    #[test]
+    fn example_part_2() {
+        let input = Day5::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day5::part2(&input).unwrap(), 46);
+    }
+
+    #[test]
+    fn real_input_part_1() {
+        let input = Day5::parse(include_str!("../../resources/d5/input.txt")).unwrap();
+        assert_eq!(Day5::part1(&input).unwrap(), 484023871);
+    }
+
+    #[test]
+    fn real_input_part_2() {
+        let input = Day5::parse(include_str!("../../resources/d5/input.txt")).unwrap();
+        assert_eq!(Day5::part2(&input).unwrap(), 46294175);
+    }
+
+    #[test]
+    fn seed_positions() {
+        let almanac = Almanac::parse(DUMMY_INPUT_P1).unwrap();
+        let locations: Vec<i64> = [79, 14, 55, 13]
+            .iter()
+            .map(|seed| find_seed_position(*seed, &almanac.maps))
+            .collect();
+        assert_eq!(locations, vec![82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn disjoint_range_is_left_untouched() {
+        assert_eq!(moved(0, 10), vec![(0, 10)]);
+        assert_eq!(moved(20, 25), vec![(20, 25)]);
+    }
+
+    #[test]
+    fn contained_range_is_translated() {
+        assert_eq!(moved(12, 15), vec![(102, 105)]);
+        assert_eq!(moved(10, 20), vec![(100, 110)]);
+    }
+
+    #[test]
+    fn range_overlapping_the_start_is_split() {
+        assert_eq!(moved(5, 15), vec![(5, 10), (100, 105)]);
+    }
+
+    #[test]
+    fn range_overlapping_the_end_is_split() {
+        assert_eq!(moved(15, 25), vec![(20, 25), (105, 110)]);
+    }
+
+    #[test]
+    fn range_containing_the_source_is_split_in_three() {
+        assert_eq!(moved(0, 30), vec![(0, 10), (20, 30), (100, 110)]);
+    }
+
+    #[test]
+    fn chain_must_lead_from_seed_to_location() {
+        let err = Day5::parse("seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 3: the maps do not lead from 'seed' to 'location'"
+        );
+    }
+
+    #[test]
+    fn solvers_report_a_missing_chain() {
+        let almanac = Almanac::parse("seeds: 1 2\n\nseed-to-soil map:\n1 2 3").unwrap();
+        let message = "the maps do not lead from 'seed' to 'location'";
+        assert_eq!(solve_part_1(&almanac).unwrap_err().to_string(), message);
+        assert_eq!(solve_part_2(&almanac).unwrap_err().to_string(), message);
+    }
+
+    #[test]
+    fn seeds_must_come_in_pairs() {
+        let input = Day5::parse("seeds: 5\n\nseed-to-location map:\n1 2 3\n").unwrap();
+        assert_eq!(Day5::part1(&input).unwrap(), 5);
+        assert_eq!(
+            Day5::part2(&input).unwrap_err().to_string(),
+            "part two reads the seeds as start and length pairs, 5 has no length"
+        );
+        let input = Day5::parse("seeds: 5 0\n\nseed-to-location map:\n1 2 3\n").unwrap();
+        assert!(Day5::part2(&input).is_err());
+    }
+
+    #[test]
+    fn ranges_past_i64_are_reported() {
+        let error = |s: &str| Almanac::parse(s).err().unwrap().to_string();
+        assert_eq!(
+            error("seeds: 1 2 9223372036854775807 1\n"),
+            "line 1, column 32: seed range 9223372036854775807 1 runs past the largest 64-bit number"
+        );
+        assert_eq!(
+            error("seeds: 1 2\n\nseed-to-soil map:\n9223372036854775800 0 10\n"),
+            "line 4, column 23: a range of 10 runs past the largest 64-bit number"
+        );
+    }
+
+    #[test]
+    fn range_before_any_header_is_reported() {
+        let err = Almanac::parse("seeds: 1 2\n\n1 2 3\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "line 3: range listed before any map header"
+        );
+    }
 }
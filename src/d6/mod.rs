@@ -1,69 +1,88 @@
-use regex::Regex;
-
-use std::collections::HashMap;
-use std::fmt::Display;
-use std::hash::Hash;
-use std::io::Write;
 use std::iter::zip;
-use std::ops::Deref;
-use std::path::Path;
-use std::{fs, io, vec};
 
-const DAY: i8 = 6;
+use crate::error::{Error, Result};
+use crate::scan;
+use crate::solution::Solution;
 
 const DUMMY_INPUT_P1: &str = "Time:      7  15   30
 Distance:  9  40  200";
-// write a regex to parse the input
 
-fn read_numbers_whitespace(s: &str) -> Vec<i64> {
-    s.split_whitespace()
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>()
+const TIME_LABEL: &str = "Time:";
+const DISTANCE_LABEL: &str = "Distance:";
+
+// the "Time:" and "Distance:" lines, labels included
+fn read_sheet_lines(s: &str) -> Result<(&str, &str)> {
+    let mut lines = s.lines();
+    let time_line = lines
+        .next()
+        .filter(|line| line.starts_with(TIME_LABEL))
+        .ok_or(Error::parse(
+            1,
+            "expected 'Time:' followed by the race times",
+        ))?;
+    let distance_line = lines
+        .next()
+        .filter(|line| line.starts_with(DISTANCE_LABEL))
+        .ok_or(Error::parse(
+            2,
+            "expected 'Distance:' followed by the record distances",
+        ))?;
+    return Ok((time_line, distance_line));
+}
+
+// every field after the label, so errors point at the right column
+fn read_numbers_whitespace(s: &str, line: usize) -> Result<Vec<i64>> {
+    return scan::fields(s)
+        .skip(1)
+        .map(|token| token.parse::<i64>(line))
+        .collect();
+}
+
+pub fn read_input(s: &str) -> Result<(Vec<i64>, Vec<i64>)> {
+    let (time_line, distance_line) = read_sheet_lines(s)?;
+    let times = read_numbers_whitespace(time_line, 1)?;
+    let distances = read_numbers_whitespace(distance_line, 2)?;
+    if times.len() != distances.len() {
+        return Err(Error::parse(
+            2,
+            format!(
+                "expected {} distances, found {}",
+                times.len(),
+                distances.len()
+            ),
+        ));
+    }
+    return Ok((times, distances));
 }
 
-fn read_input(s: &str) -> (Vec<i64>, Vec<i64>) {
-    let (time_s, distance_s) = s.split_once("\n").unwrap();
-    let times = read_numbers_whitespace(time_s.split_once(":").unwrap().1);
-    let distances = read_numbers_whitespace(distance_s.split_once(":").unwrap().1);
-    return (times, distances);
+// part two reads each line as a single number, ignoring the spaces between digits
+fn read_joined_number(s: &str, line: usize) -> Result<i64> {
+    let digits = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    return digits
+        .parse()
+        .map_err(|_| Error::parse(line, format!("expected a number, found '{}'", digits)));
 }
 
-fn read_input_p2(s: &str) -> (i64, i64) {
-    let (time_s, distance_s) = s.split_once("\n").unwrap();
-    let time_s = time_s
-        .split_once(":")
-        .unwrap()
-        .1
-        .chars()
-        .filter(|c| c.is_numeric())
-        .collect::<String>();
-    let time: i64 = time_s.parse().unwrap();
-
-    let distance_s = distance_s
-        .split_once(":")
-        .unwrap()
-        .1
-        .chars()
-        .filter(|c| c.is_numeric())
-        .collect::<String>();
-    let distance: i64 = distance_s.parse().unwrap();
-    return (time, distance);
+pub fn read_input_p2(s: &str) -> Result<(i64, i64)> {
+    let (time_line, distance_line) = read_sheet_lines(s)?;
+    let time = read_joined_number(&time_line[TIME_LABEL.len()..], 1)?;
+    let distance = read_joined_number(&distance_line[DISTANCE_LABEL.len()..], 2)?;
+    return Ok((time, distance));
 }
 
-struct RaceSimulation {
-    charge_time: i64,
-    final_distance: i64,
+pub struct RaceSimulation {
+    pub charge_time: i64,
+    pub final_distance: i64,
 }
 
-fn compute_results(available_time: i64, min_distance: i64) -> Vec<RaceSimulation> {
+pub fn compute_results(available_time: i64, min_distance: i64) -> Vec<RaceSimulation> {
     let acceleration = 1; //
     return (1..available_time)
-        .into_iter()
         .map(|charge_time| {
             let cur_speed = acceleration * charge_time;
             let final_distance = cur_speed * (available_time - charge_time);
             return RaceSimulation {
-                charge_time: charge_time,
+                charge_time,
                 final_distance,
             };
         })
@@ -71,55 +90,89 @@ fn compute_results(available_time: i64, min_distance: i64) -> Vec<RaceSimulation
         .collect();
 }
 
-fn solve_part_1(s: &str) -> i64 {
-    let (times, distances) = read_input(s);
-    let available_combinations: Vec<Vec<RaceSimulation>> = zip(times.clone(), distances.clone())
-        .into_iter()
-        .map(|(t, d)| compute_results(t, d))
+pub fn solve_part_1((times, distances): &(Vec<i64>, Vec<i64>)) -> i64 {
+    let available_combinations: Vec<Vec<RaceSimulation>> = zip(times, distances)
+        .map(|(t, d)| compute_results(*t, *d))
         .collect();
 
     return available_combinations
         .iter()
-        .fold(1, |acc: i64, race_simulations: &Vec<RaceSimulation>| {
-            (race_simulations.len() as i64) * acc
-        });
+        .map(|race_simulations| race_simulations.len() as i64)
+        .product();
 }
 
-fn solve_part_2(s: &str) -> i64 {
-    let (available_time, min_distance) = read_input_p2(s);
+pub fn solve_part_2(&(available_time, min_distance): &(i64, i64)) -> i64 {
     let available_combinations: Vec<RaceSimulation> = compute_results(available_time, min_distance);
 
     return available_combinations.iter().len() as i64;
 }
 
-fn gather_input_and_solve_p1() {
-    let res = solve_part_1(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
-
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
-
-    let res = solve_part_1(input.as_str());
-    println!("Result: {}", res);
+pub struct Sheet {
+    races: (Vec<i64>, Vec<i64>),
+    single_race: (i64, i64),
 }
 
-fn gather_input_and_solve_p2() {
-    let res = solve_part_2(DUMMY_INPUT_P1);
+pub struct Day6;
 
-    println!("Result: {}", res);
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Wait For It";
+    const EXAMPLE: &'static str = DUMMY_INPUT_P1;
 
-    let res = solve_part_2(input.as_str());
-    println!("Result: {}", res);
+    type Input<'a> = Sheet;
+    type Answer = i64;
+
+    fn parse(input: &str) -> Result<Sheet> {
+        return Ok(Sheet {
+            races: read_input(input)?,
+            single_race: read_input_p2(input)?,
+        });
+    }
+    fn part1(input: &Sheet) -> Result<i64> {
+        return Ok(solve_part_1(&input.races));
+    }
+    fn part2(input: &Sheet) -> Result<i64> {
+        return Ok(solve_part_2(&input.single_race));
+    }
 }
 
-// Define a function to solve the code advent problem
-pub fn solve() {
-    gather_input_and_solve_p1();
-    gather_input_and_solve_p2();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day6::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day6::part1(&input).unwrap(), 288);
+        assert_eq!(Day6::part2(&input).unwrap(), 71503);
+    }
+
+    #[test]
+    fn real_input_part_1() {
+        let input = Day6::parse(include_str!("../../resources/d6/input.txt")).unwrap();
+        assert_eq!(Day6::part1(&input).unwrap(), 5133600);
+    }
+
+    #[test]
+    fn compute_results_keeps_only_winning_charges() {
+        let charges: Vec<i64> = compute_results(7, 9)
+            .iter()
+            .map(|race| race.charge_time)
+            .collect();
+        assert_eq!(charges, vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn part_2_joins_the_digits() {
+        assert_eq!(read_input_p2(DUMMY_INPUT_P1).unwrap(), (71530, 940200));
+    }
+
+    #[test]
+    fn bad_number_points_at_its_column() {
+        let err = read_input("Time:  7 1x\nDistance: 9 40").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 10: expected a number, found '1x'"
+        );
+    }
 }
@@ -1,17 +1,8 @@
-use regex::Regex;
-
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fmt::Display;
-use std::hash::Hash;
-use std::io::Write;
-use std::iter::zip;
-use std::ops::Deref;
-use std::path::Path;
-use std::sync::Arc;
-use std::{fs, io, vec};
 
-const DAY: i8 = 7;
+use crate::error::{Error, Result};
+use crate::solution::Solution;
 
 const DUMMY_INPUT_P1: &str = "32T3K 765
 T55J5 684
@@ -19,14 +10,29 @@ KK677 28
 KTJJT 220
 QQQJA 483";
 
-fn read_input(s: &str) -> Vec<Hand> {
+const CARD_LABELS: [char; 13] = [
+    '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+];
+
+pub fn read_input(s: &str) -> Result<Vec<Hand>> {
     return s
         .lines()
-        .into_iter()
-        .map(|s| {
-            let hand_s = s.split_once(" ").unwrap();
-            let bid = hand_s.1.parse::<i64>().unwrap();
-            return Hand::new(hand_s.0, bid);
+        .enumerate()
+        .map(|(line_num, s)| {
+            let line_num = line_num + 1;
+            let hand_s = s
+                .split_once(" ")
+                .ok_or(Error::parse(line_num, "expected a hand and a bid"))?;
+            if hand_s.0.chars().count() != 5 {
+                return Err(Error::parse(line_num, "a hand has exactly five cards"));
+            }
+            if let Some(pos) = hand_s.0.chars().position(|c| !CARD_LABELS.contains(&c)) {
+                return Err(Error::parse_at(line_num, pos + 1, "unknown card label"));
+            }
+            let bid = hand_s.1.trim().parse::<i64>().map_err(|_| {
+                Error::parse_at(line_num, hand_s.0.len() + 2, "expected an integer bid")
+            })?;
+            return Ok(Hand::new(hand_s.0, bid));
         })
         .collect();
 }
@@ -46,7 +52,7 @@ fn read_input(s: &str) -> Vec<Hand> {
 // write a function to determine the type of hand
 
 #[derive(Debug, Clone, Copy)]
-struct Card {
+pub struct Card {
     c: char,
 }
 
@@ -60,32 +66,30 @@ impl Eq for Card {}
 
 impl PartialOrd for Card {
     fn partial_cmp(self: &Card, other: &Card) -> Option<Ordering> {
-        if *self == *other {
-            return Some(Ordering::Equal);
-        }
-        let cards = vec![
-            '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
-        ];
-        let self_idx = cards.iter().position(|&c| c == self.c).unwrap();
-        let other_idx = cards.iter().position(|&c| c == other.c).unwrap();
-        return Some(self_idx.cmp(&other_idx));
+        return Some(self.cmp(other));
     }
 }
 
 impl Ord for Card {
     fn cmp(self: &Card, other: &Card) -> Ordering {
-        return self.partial_cmp(other).unwrap();
+        if *self == *other {
+            return Ordering::Equal;
+        }
+        let self_idx = CARD_LABELS.iter().position(|&c| c == self.c).unwrap();
+        let other_idx = CARD_LABELS.iter().position(|&c| c == other.c).unwrap();
+        return self_idx.cmp(&other_idx);
     }
 }
 
-struct Hand {
+#[derive(Debug)]
+pub struct Hand {
     hand: Vec<Card>,
     card_tuples: HashMap<char, i32>,
     bid: i64,
 }
 
 impl Hand {
-    fn new(hand: &str, bid: i64) -> Hand {
+    pub fn new(hand: &str, bid: i64) -> Hand {
         let mut card_tuples: HashMap<char, i32> = HashMap::new();
         for c in hand.chars() {
             card_tuples
@@ -100,9 +104,6 @@ impl Hand {
             bid,
         };
     }
-    fn high_card(&self) -> Card {
-        return self.hand.iter().max().unwrap().clone();
-    }
     fn pair(&self) -> Option<Card> {
         self.card_tuples.iter().find_map(|(card, count)| {
             if *count == 2 {
@@ -146,7 +147,7 @@ impl Hand {
             return None;
         })
     }
-    fn get_rank(&self) -> i32 {
+    pub fn get_rank(&self) -> i32 {
         if self.five_of_a_kind().is_some() {
             return 7;
         } else if self.four_of_a_kind().is_some() {
@@ -175,65 +176,95 @@ impl Eq for Hand {}
 
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Hand) -> Option<Ordering> {
-        if self.get_rank() > other.get_rank() {
-            return Some(Ordering::Greater);
-        } else if self.get_rank() < other.get_rank() {
-            return Some(Ordering::Less);
-        } else if self.hand > other.hand {
-            return Some(Ordering::Greater);
-        } else if self.hand < other.hand {
-            return Some(Ordering::Less);
-        }
-        return Some(Ordering::Equal);
+        return Some(self.cmp(other));
     }
 }
 
 impl Ord for Hand {
     fn cmp(&self, other: &Hand) -> Ordering {
-        return self.partial_cmp(other).unwrap();
+        if self.get_rank() > other.get_rank() {
+            return Ordering::Greater;
+        } else if self.get_rank() < other.get_rank() {
+            return Ordering::Less;
+        } else if self.hand > other.hand {
+            return Ordering::Greater;
+        } else if self.hand < other.hand {
+            return Ordering::Less;
+        }
+        return Ordering::Equal;
     }
 }
 
-fn solve_part_1(s: &str) -> i64 {
-    let mut v: Vec<Hand> = read_input(s);
+pub fn solve_part_1(hands: &[Hand]) -> i64 {
+    let mut v: Vec<&Hand> = hands.iter().collect();
     v.sort();
 
     return v.iter().enumerate().fold(0, |acc, (i, hand)| {
         return acc + hand.bid * (i as i64 + 1);
     });
 }
-fn solve_part_2(s: &str) -> i64 {
+pub fn solve_part_2(_hands: &[Hand]) -> i64 {
     return 0;
 }
 
-fn gather_input_and_solve_p1() {
-    let res = solve_part_1(DUMMY_INPUT_P1);
-    println!("Result: {}", res);
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Camel Cards";
+    const EXAMPLE: &'static str = DUMMY_INPUT_P1;
 
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
+    type Input<'a> = Vec<Hand>;
+    type Answer = i64;
 
-    let res = solve_part_1(input.as_str());
-    println!("Result: {}", res);
+    fn parse(input: &str) -> Result<Vec<Hand>> {
+        return read_input(input);
+    }
+    fn part1(input: &Vec<Hand>) -> Result<i64> {
+        return Ok(solve_part_1(input));
+    }
+    fn part2(input: &Vec<Hand>) -> Result<i64> {
+        return Ok(solve_part_2(input));
+    }
 }
 
-fn gather_input_and_solve_p2() {
-    let res = solve_part_2(DUMMY_INPUT_P1);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day7::parse(DUMMY_INPUT_P1).unwrap();
+        assert_eq!(Day7::part1(&input).unwrap(), 6440);
+    }
 
-    println!("Result: {}", res);
-    let path = Path::new("resources")
-        .join(format!("d{}", DAY))
-        .join("input.txt");
-    let input = fs::read_to_string(path).expect("Something went wrong reading the file");
+    #[test]
+    fn real_input() {
+        let input = Day7::parse(include_str!("../../resources/d7/input.txt")).unwrap();
+        assert_eq!(Day7::part1(&input).unwrap(), 248453531);
+    }
 
-    let res = solve_part_2(input.as_str());
-    println!("Result: {}", res);
-}
+    #[test]
+    fn hand_ranks() {
+        let ranks: Vec<i32> = [
+            "AAAAA", "AA8AA", "23332", "TTT98", "23432", "A23A4", "23456",
+        ]
+        .iter()
+        .map(|hand| Hand::new(hand, 0).get_rank())
+        .collect();
+        assert_eq!(ranks, vec![7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn equal_ranks_compare_card_by_card() {
+        assert!(Hand::new("33332", 0) > Hand::new("2AAAA", 0));
+        assert!(Hand::new("77888", 0) > Hand::new("77788", 0));
+        assert!(Hand::new("KK677", 0) > Hand::new("KTJJT", 0));
+    }
 
-// Define a function to solve the code advent problem
-pub fn solve() {
-    gather_input_and_solve_p1();
-    gather_input_and_solve_p2();
+    #[test]
+    fn unknown_card_is_reported() {
+        let err = read_input("32T3K 765\nT55X5 684").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 4: unknown card label");
+    }
 }
@@ -0,0 +1,62 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(String, io::Error),
+    /// Malformed puzzle input, `line` and `column` are 1-based.
+    Parse {
+        line: usize,
+        column: Option<usize>,
+        message: String,
+    },
+    /// An error while reading a supporting file such as answers.toml.
+    InFile(PathBuf, Box<Error>),
+    Panic(String),
+    /// A question the input cannot answer, such as a lookup between unknown categories.
+    Query(String),
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+    pub fn parse(line: usize, message: impl Into<String>) -> Error {
+        return Error::Parse {
+            line,
+            column: None,
+            message: message.into(),
+        };
+    }
+
+    pub fn parse_at(line: usize, column: usize, message: impl Into<String>) -> Error {
+        return Error::Parse {
+            line,
+            column: Some(column),
+            message: message.into(),
+        };
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Error::Io(what, err) => write!(f, "cannot read {}: {}", what, err),
+            Error::Parse {
+                line,
+                column: None,
+                message,
+            } => write!(f, "line {}: {}", line, message),
+            Error::Parse {
+                line,
+                column: Some(column),
+                message,
+            } => write!(f, "line {}, column {}: {}", line, column, message),
+            Error::InFile(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::Panic(msg) => write!(f, "solver panicked: {}", msg),
+            Error::Query(msg) => write!(f, "{}", msg),
+        };
+    }
+}
+
+impl std::error::Error for Error {}
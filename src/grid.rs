@@ -0,0 +1,326 @@
+use std::collections::BTreeSet;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{Error, Result};
+
+/// A rectangular grid stored row-major, indexed by `(x, y)` with `x` the column and `y` the row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+const OFFSETS_4: [(i64, i64); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const OFFSETS_8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Which cells around a cell count as its neighbours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stencil {
+    /// Cells sharing an edge.
+    VonNeumann,
+    /// Cells sharing an edge or a corner.
+    Moore,
+    /// Any set of `(dx, dy)` offsets.
+    Custom(Vec<(i64, i64)>),
+}
+
+impl Stencil {
+    pub fn offsets(&self) -> &[(i64, i64)] {
+        return match self {
+            Stencil::VonNeumann => &OFFSETS_4,
+            Stencil::Moore => &OFFSETS_8,
+            Stencil::Custom(offsets) => offsets,
+        };
+    }
+}
+
+/// A horizontal run of cells treated as a single cell, such as a multi-digit number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub x: usize,
+    pub y: usize,
+    pub len: usize,
+}
+
+impl Span {
+    pub fn cell(x: usize, y: usize) -> Span {
+        return Span { x, y, len: 1 };
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        return y == self.y && self.x <= x && x < self.x + self.len;
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "cells do not fill the grid");
+        return Grid {
+            width,
+            height,
+            cells,
+        };
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        return Grid::new(width, height, vec![value; width * height]);
+    }
+
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    pub fn in_bounds(&self, x: i64, y: i64) -> bool {
+        return 0 <= x && x < self.width as i64 && 0 <= y && y < self.height as i64;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        return Some(&self.cells[y * self.width + x]);
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        return Some(&mut self.cells[y * self.width + x]);
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        return &self.cells[y * self.width..(y + 1) * self.width];
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        return self.cells.chunks(self.width.max(1));
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of bounds", x);
+        return self.cells.iter().skip(x).step_by(self.width);
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        return (0..self.width).map(|x| self.column(x));
+    }
+
+    /// Every cell with its `(x, y)` position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        return self
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| ((i % self.width, i / self.width), cell));
+    }
+
+    /// Positions around `span` under `stencil`, inside the grid and outside the span itself,
+    /// each listed once in row-major order.
+    pub fn neighbours(&self, span: Span, stencil: &Stencil) -> Vec<(usize, usize)> {
+        let mut positions = BTreeSet::new();
+        for x in span.x..span.x + span.len {
+            for (dx, dy) in stencil.offsets() {
+                let (nx, ny) = (x as i64 + dx, span.y as i64 + dy);
+                if !self.in_bounds(nx, ny) || span.contains(nx as usize, ny as usize) {
+                    continue;
+                }
+                // (y, x) so the set iterates row by row
+                positions.insert((ny as usize, nx as usize));
+            }
+        }
+        return positions.into_iter().map(|(y, x)| (x, y)).collect();
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        return self.neighbours(Span::cell(x, y), &Stencil::VonNeumann);
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        return self.neighbours(Span::cell(x, y), &Stencil::Moore);
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        return Grid::new(self.width, self.height, self.cells.iter().map(f).collect());
+    }
+}
+
+impl Grid<char> {
+    /// One row per line; every line must have the same number of characters.
+    pub fn parse(s: &str) -> Result<Grid<char>> {
+        let width = s.lines().next().map_or(0, |line| line.chars().count());
+        if width == 0 {
+            return Err(Error::parse(1, "empty grid"));
+        }
+        let mut cells = vec![];
+        let mut height = 0;
+        for (row_num, line) in s.lines().enumerate() {
+            let len = line.chars().count();
+            if len != width {
+                return Err(Error::parse(
+                    row_num + 1,
+                    format!("expected {} columns, found {}", width, len),
+                ));
+            }
+            cells.extend(line.chars());
+            height += 1;
+        }
+        return Ok(Grid::new(width, height, cells));
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        return self.get(x, y).expect("position out of bounds");
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        return self.get_mut(x, y).expect("position out of bounds");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_get() {
+        let grid = Grid::parse("abc\ndef").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(2, 0), Some(&'c'));
+        assert_eq!(grid[(0, 1)], 'd');
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+    }
+
+    #[test]
+    fn ragged_lines_are_rejected() {
+        let err = Grid::parse("abc\nde").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected 3 columns, found 2");
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = Grid::parse("abc\ndef").unwrap();
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        assert_eq!(rows, vec!["abc", "def"]);
+        let columns: Vec<String> = grid.columns().map(|col| col.collect()).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+    }
+
+    #[test]
+    fn iter_yields_positions() {
+        let grid = Grid::parse("ab\ncd").unwrap();
+        let cells: Vec<((usize, usize), char)> = grid.iter().map(|(p, c)| (p, *c)).collect();
+        assert_eq!(
+            cells,
+            vec![((0, 0), 'a'), ((1, 0), 'b'), ((0, 1), 'c'), ((1, 1), 'd')]
+        );
+    }
+
+    #[test]
+    fn neighbours_stay_inside_the_grid() {
+        let grid = Grid::filled(4, 2, 0);
+        assert_eq!(grid.neighbours4(0, 0), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8(3, 1), vec![(2, 0), (3, 0), (2, 1)]);
+        assert_eq!(grid.neighbours8(1, 1).len(), 5);
+    }
+
+    #[test]
+    fn span_neighbours_exclude_the_span() {
+        let grid = Grid::filled(7, 3, 0);
+        let span = Span { x: 2, y: 1, len: 3 };
+        assert_eq!(
+            grid.neighbours(span, &Stencil::Moore),
+            vec![
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (5, 0),
+                (1, 1),
+                (5, 1),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (5, 2)
+            ]
+        );
+        assert_eq!(
+            grid.neighbours(span, &Stencil::VonNeumann),
+            vec![
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (1, 1),
+                (5, 1),
+                (2, 2),
+                (3, 2),
+                (4, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn span_on_the_edge_of_a_wide_grid() {
+        // wider than tall, so mixing up width and height would clip the right side
+        let grid = Grid::filled(7, 2, 0);
+        let span = Span { x: 5, y: 0, len: 2 };
+        assert_eq!(
+            grid.neighbours(span, &Stencil::Moore),
+            vec![(4, 0), (4, 1), (5, 1), (6, 1)]
+        );
+    }
+
+    #[test]
+    fn custom_stencil() {
+        let knight = Stencil::Custom(vec![
+            (1, 2),
+            (2, 1),
+            (2, -1),
+            (1, -2),
+            (-1, -2),
+            (-2, -1),
+            (-2, 1),
+            (-1, 2),
+        ]);
+        let grid = Grid::filled(3, 5, 0);
+        assert_eq!(
+            grid.neighbours(Span::cell(0, 0), &knight),
+            vec![(2, 1), (1, 2)]
+        );
+        assert_eq!(
+            grid.neighbours(Span::cell(1, 2), &knight),
+            vec![(0, 0), (2, 0), (0, 4), (2, 4)]
+        );
+    }
+
+    #[test]
+    fn map_and_index_mut() {
+        let mut grid = Grid::parse("1.\n.2").unwrap().map(|c| c.to_digit(10));
+        grid[(1, 0)] = Some(7);
+        assert_eq!(grid.row(0), &[Some(1), Some(7)]);
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+
+/// The integers in `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Interval {
+        return Interval { start, end };
+    }
+
+    pub fn with_len(start: i64, len: i64) -> Interval {
+        return Interval {
+            start,
+            end: start + len,
+        };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.end <= self.start;
+    }
+
+    pub fn len(&self) -> i64 {
+        return (self.end - self.start).max(0);
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        return self.start <= x && x < self.end;
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let overlap = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        return (!overlap.is_empty()).then_some(overlap);
+    }
+
+    /// What is left of `self` once `other` is taken out: the part before it and the part after.
+    pub fn difference(&self, other: &Interval) -> (Option<Interval>, Option<Interval>) {
+        if self.intersection(other).is_none() {
+            return (Some(*self).filter(|i| !i.is_empty()), None);
+        }
+        let before = Interval::new(self.start, other.start);
+        let after = Interval::new(other.end, self.end);
+        return (
+            Some(before).filter(|i| !i.is_empty()),
+            Some(after).filter(|i| !i.is_empty()),
+        );
+    }
+
+    pub fn shift(&self, delta: i64) -> Interval {
+        return Interval::new(self.start + delta, self.end + delta);
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}..{}", self.start, self.end);
+    }
+}
+
+/// A set of integers stored as sorted intervals that neither overlap nor touch, so equal sets
+/// always have equal representations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+// sorts and merges overlapping or adjacent intervals, dropping empty ones
+fn normalise(mut intervals: Vec<Interval>) -> Vec<Interval> {
+    intervals.retain(|i| !i.is_empty());
+    intervals.sort();
+    let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+            _ => merged.push(interval),
+        }
+    }
+    return merged;
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        return IntervalSet::default();
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        return &self.intervals;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.intervals.is_empty();
+    }
+
+    /// How many integers the set holds.
+    pub fn len(&self) -> i64 {
+        return self.intervals.iter().map(|i| i.len()).sum();
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        return self.intervals.first().map(|i| i.start);
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.intervals.partition_point(|i| i.end <= x);
+        return self.intervals.get(i).is_some_and(|i| i.contains(x));
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        self.intervals = normalise(intervals);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        return self
+            .intervals
+            .iter()
+            .chain(&other.intervals)
+            .cloned()
+            .collect();
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        // both sides are sorted, so walk them together
+        let (mut a, mut b) = (0, 0);
+        let mut intervals = vec![];
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (&self.intervals[a], &other.intervals[b]);
+            if let Some(overlap) = x.intersection(y) {
+                intervals.push(overlap);
+            }
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        return IntervalSet { intervals };
+    }
+
+    pub fn intersection_with(&self, interval: Interval) -> IntervalSet {
+        return self.intersection(&IntervalSet::from(interval));
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = vec![];
+        let mut b = 0;
+        for x in &self.intervals {
+            let mut rest = Some(*x);
+            // skip what ends before this interval, the rest is sorted after it
+            while b < other.intervals.len() && other.intervals[b].end <= x.start {
+                b += 1;
+            }
+            let mut i = b;
+            while let (Some(r), Some(y)) = (rest, other.intervals.get(i)) {
+                if y.start >= r.end {
+                    break;
+                }
+                let (before, after) = r.difference(y);
+                intervals.extend(before);
+                rest = after;
+                i += 1;
+            }
+            intervals.extend(rest);
+        }
+        return IntervalSet { intervals };
+    }
+
+    pub fn shift(&self, delta: i64) -> IntervalSet {
+        return IntervalSet {
+            intervals: self.intervals.iter().map(|i| i.shift(delta)).collect(),
+        };
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> IntervalSet {
+        return IntervalSet {
+            intervals: normalise(vec![interval]),
+        };
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> IntervalSet {
+        return IntervalSet {
+            intervals: normalise(iter.into_iter().collect()),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(bounds: &[(i64, i64)]) -> IntervalSet {
+        return bounds.iter().map(|(s, e)| Interval::new(*s, *e)).collect();
+    }
+
+    fn bounds(set: &IntervalSet) -> Vec<(i64, i64)> {
+        return set.intervals().iter().map(|i| (i.start, i.end)).collect();
+    }
+
+    #[test]
+    fn interval_intersection_and_difference() {
+        let a = Interval::new(10, 20);
+        assert_eq!(
+            a.intersection(&Interval::new(15, 30)),
+            Some(Interval::new(15, 20))
+        );
+        assert_eq!(a.intersection(&Interval::new(20, 30)), None);
+        assert_eq!(
+            a.difference(&Interval::new(12, 15)),
+            (Some(Interval::new(10, 12)), Some(Interval::new(15, 20)))
+        );
+        assert_eq!(a.difference(&Interval::new(5, 25)), (None, None));
+        assert_eq!(a.difference(&Interval::new(30, 40)), (Some(a), None));
+        assert_eq!(a.shift(-10), Interval::with_len(0, 10));
+    }
+
+    #[test]
+    fn sets_are_normalised() {
+        let s = set(&[(5, 8), (0, 3), (3, 4), (7, 10), (20, 20)]);
+        assert_eq!(bounds(&s), vec![(0, 4), (5, 10)]);
+        assert_eq!(s.len(), 9);
+        assert_eq!(s.min(), Some(0));
+        assert!(s.contains(9) && !s.contains(4) && !s.contains(10));
+    }
+
+    #[test]
+    fn union_and_intersection() {
+        let a = set(&[(0, 5), (10, 15)]);
+        let b = set(&[(3, 12), (14, 20)]);
+        assert_eq!(bounds(&a.union(&b)), vec![(0, 20)]);
+        assert_eq!(
+            bounds(&a.intersection(&b)),
+            vec![(3, 5), (10, 12), (14, 15)]
+        );
+        assert!(a.intersection(&IntervalSet::new()).is_empty());
+    }
+
+    #[test]
+    fn difference_splits_around_holes() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(2, 4), (6, 8), (9, 22), (25, 26)]);
+        assert_eq!(
+            bounds(&a.difference(&b)),
+            vec![(0, 2), (4, 6), (8, 9), (22, 25), (26, 30)]
+        );
+        assert!(a.difference(&a).is_empty());
+        assert_eq!(a.difference(&IntervalSet::new()), a);
+    }
+
+    #[test]
+    fn insert_and_shift() {
+        let mut s = set(&[(0, 2)]);
+        s.insert(Interval::new(2, 5));
+        s.insert(Interval::new(10, 11));
+        assert_eq!(bounds(&s.shift(100)), vec![(100, 105), (110, 111)]);
+    }
+}
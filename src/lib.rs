@@ -0,0 +1,21 @@
+#![allow(clippy::needless_return)]
+
+pub mod answers;
+pub mod bench;
+pub mod color;
+pub mod d1;
+pub mod d2;
+pub mod d3;
+pub mod d4;
+pub mod d5;
+pub mod d6;
+pub mod d7;
+pub mod error;
+pub mod grid;
+pub mod interval;
+pub mod json;
+pub mod log;
+pub mod registry;
+pub mod runner;
+pub mod scan;
+pub mod solution;
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the crate reports on stderr, from only errors up to every intermediate step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Level {
+    Error = 1,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+const LEVELS: [Level; 5] = [
+    Level::Error,
+    Level::Warn,
+    Level::Info,
+    Level::Debug,
+    Level::Trace,
+];
+
+// warnings and errors only, so answers are all that a default run prints
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+impl Level {
+    /// The level reached with `-v` given `count` times: info, debug and then trace.
+    pub fn from_verbosity(count: usize) -> Level {
+        return Level::Warn.louder(count);
+    }
+
+    /// The level `steps` above this one, stopping at trace.
+    pub fn louder(self, steps: usize) -> Level {
+        return LEVELS[(self as usize - 1)
+            .saturating_add(steps)
+            .min(LEVELS.len() - 1)];
+    }
+
+    fn name(&self) -> &'static str {
+        return match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        };
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.pad(self.name());
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Level, String> {
+        return LEVELS
+            .iter()
+            .find(|level| level.name() == s)
+            .copied()
+            .ok_or(format!(
+                "unknown log level '{}', expected error, warn, info, debug or trace",
+                s
+            ));
+    }
+}
+
+pub fn set_level(level: Level) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    let max = MAX_LEVEL.load(Ordering::Relaxed);
+    return LEVELS[max as usize - 1];
+}
+
+pub fn enabled(level: Level) -> bool {
+    return level as u8 <= MAX_LEVEL.load(Ordering::Relaxed);
+}
+
+fn format_line(level: Level, args: fmt::Arguments) -> String {
+    return format!("[{:<5}] {}", level, args);
+}
+
+// called by the macros once `enabled` has said yes, so disabled messages are never formatted
+pub fn write(level: Level, args: fmt::Arguments) {
+    eprintln!("{}", format_line(level, args));
+}
+
+/// Logs a message at `level` on stderr, formatting it only when the level is enabled.
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_from_flags() {
+        assert_eq!(Level::from_verbosity(0), Level::Warn);
+        assert_eq!(Level::from_verbosity(1), Level::Info);
+        assert_eq!(Level::from_verbosity(3), Level::Trace);
+        assert_eq!(Level::from_verbosity(10), Level::Trace);
+        assert_eq!(Level::Error.louder(2), Level::Info);
+        assert_eq!("debug".parse(), Ok(Level::Debug));
+        assert!("loud".parse::<Level>().is_err());
+        assert!(Level::Error < Level::Trace);
+    }
+
+    #[test]
+    fn lines_carry_their_level() {
+        assert_eq!(
+            format_line(Level::Info, format_args!("{} maps", 7)),
+            "[info ] 7 maps"
+        );
+    }
+}
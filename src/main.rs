@@ -1,30 +1,215 @@
+#![allow(clippy::needless_return)]
+
 use std::env;
-mod d1;
-mod d2;
-mod d3;
-mod d4;
-mod d5;
-mod d6;
-mod d7;
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Please specify a day");
-        return;
+use std::fs;
+use std::process::ExitCode;
+
+use advent_of_code_2023::d3::{self, Day3};
+use advent_of_code_2023::d4::{self, Day4, ParseMode};
+use advent_of_code_2023::d5::{self, Day5};
+use advent_of_code_2023::error::{Error, Result};
+use advent_of_code_2023::runner::Source;
+use advent_of_code_2023::solution::Solution;
+use advent_of_code_2023::{bench, log, registry, runner};
+use cli::{AlmanacAction, Command, SchematicAction, ScratchcardsAction};
+
+mod cli;
+
+fn schematic(action: &SchematicAction, source: &Source) -> Result<()> {
+    let input = runner::read_text(source, Day3::EXAMPLE)?;
+    let schematic = Day3::parse(&input)?;
+    match action {
+        SchematicAction::Report { json } => {
+            let report = d3::report::report(&schematic);
+            if *json {
+                print!("{}", report.to_json());
+            } else {
+                print!("{}", report.to_table());
+            }
+        }
+        SchematicAction::Gears(query) => {
+            print!("{}", d3::query::to_table(&query.matches(&schematic)?));
+        }
+        SchematicAction::Render { svg: None } => print!("{}", d3::render::to_ansi(&schematic)),
+        SchematicAction::Render { svg: Some(path) } => {
+            fs::write(path, d3::render::to_svg(&schematic))
+                .map_err(|err| Error::Io(path.display().to_string(), err))?;
+        }
+    }
+    return Ok(());
+}
+
+fn scratchcards(action: &ScratchcardsAction, mode: ParseMode, source: &Source) -> Result<()> {
+    let input = runner::read_text(source, Day4::EXAMPLE)?;
+    let deck = d4::read_deck(&input, mode)?;
+    match action {
+        ScratchcardsAction::Explain { overrun } => {
+            let explanations = d4::explain::explain(&deck, *overrun)?;
+            print!("{}", d4::explain::to_table(&explanations));
+        }
+        ScratchcardsAction::Check => match deck.first() {
+            Some(first) => println!(
+                "{} cards, each with {} winning and {} own numbers",
+                deck.len(),
+                first.winning.len(),
+                first.mine.len()
+            ),
+            None => println!("no cards"),
+        },
     }
+    return Ok(());
+}
+
+fn almanac(action: &AlmanacAction, source: &Source) -> Result<()> {
+    let input = runner::read_text(source, Day5::EXAMPLE)?;
+    let almanac = Day5::parse(&input)?;
+    match action {
+        AlmanacAction::Mapping { from, to } => {
+            print!("{}", almanac.require_mapping(from, to)?.dump())
+        }
+        AlmanacAction::Lookup { number, from, to } => {
+            println!(
+                "{} {} is {} {}",
+                from,
+                number,
+                to,
+                almanac.require_mapping(from, to)?.apply(*number)
+            );
+        }
+        AlmanacAction::Seeds(locations) => {
+            let preimage = almanac
+                .require_mapping(d5::SEED, d5::LOCATION)?
+                .preimage(&(*locations).into());
+            println!("Seeds with locations in {}:", locations);
+            for range in preimage.intervals() {
+                println!("  {}", range);
+            }
+            let ranges: Vec<String> = preimage
+                .intersection(&almanac.seed_ranges()?)
+                .intervals()
+                .iter()
+                .map(|range| range.to_string())
+                .collect();
+            println!("Among the almanac's seed ranges: {}", ranges.join(", "));
+        }
+        AlmanacAction::Check => {
+            let categories: Vec<String> =
+                almanac.categories().iter().map(|c| c.to_string()).collect();
+            println!("Chain: {}", categories.join(" -> "));
+            let overlaps = almanac.overlaps();
+            for overlap in &overlaps {
+                println!(
+                    "{}-to-{}: {} and {} overlap on {}",
+                    overlap.source, overlap.target, overlap.first, overlap.second, overlap.common
+                );
+            }
+            println!("{} overlapping source ranges", overlaps.len());
+        }
+    }
+    return Ok(());
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = match cli::parse_args(&args) {
+        Ok((command, level)) => {
+            log::set_level(level);
+            command
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
 
-    let day = args[1].as_str();
-
-    match day {
-        "1" => d1::solve(),
-        "2" => d2::solve(),
-        "3" => d3::solve(),
-        "4" => d4::solve(),
-        "5" => d5::solve(),
-        "6" => d6::solve(),
-        "7" => d7::solve(),
-        // Add more days as you implement them
-        _ => eprintln!("Day not implemented"),
+    runner::quiet_solver_panics();
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::Verify { days } => {
+            let checks: Vec<runner::Check> = days.iter().flat_map(runner::verify_day).collect();
+            runner::print_checks(&checks);
+            let failed = checks.iter().any(|check| {
+                matches!(
+                    check.verdict(),
+                    runner::Verdict::Fail | runner::Verdict::Error
+                )
+            });
+            if failed {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Bench {
+            day,
+            parts,
+            source,
+            runs,
+            json,
+        } => {
+            let res = bench::bench_day(&day, &parts, &source, runs).and_then(|bench| {
+                bench::print_bench(&bench);
+                match &json {
+                    Some(path) => bench::write_json(&bench, path),
+                    None => Ok(()),
+                }
+            });
+            if let Err(err) = res {
+                eprintln!("Day {} ({}): error: {}", day.number, source, err);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Schematic { action, source } => {
+            if let Err(err) = schematic(&action, &source) {
+                eprintln!("Day 3 ({}): error: {}", source, err);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Scratchcards {
+            action,
+            mode,
+            source,
+        } => {
+            if let Err(err) = scratchcards(&action, mode, &source) {
+                eprintln!("Day 4 ({}): error: {}", source, err);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Almanac { action, source } => {
+            if let Err(err) = almanac(&action, &source) {
+                eprintln!("Day 5 ({}): error: {}", source, err);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::GenerateDeck { spec, output } => {
+            let deck = d4::generate::generate(&spec);
+            match output {
+                Some(path) => {
+                    if let Err(err) = fs::write(&path, deck) {
+                        eprintln!("error: {}", Error::Io(path.display().to_string(), err));
+                        return ExitCode::FAILURE;
+                    }
+                }
+                None => print!("{}", deck),
+            }
+        }
+        Command::List => {
+            for day in registry::days() {
+                println!("{:>2}  {}", day.number, day.title);
+            }
+        }
+        Command::Run {
+            days,
+            parts,
+            source,
+        } => {
+            let mut ok = true;
+            for day in &days {
+                ok &= runner::run_day(day, &parts, source.as_ref());
+            }
+            if !ok {
+                return ExitCode::FAILURE;
+            }
+        }
     }
+    return ExitCode::SUCCESS;
 }
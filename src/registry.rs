@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::solution::{Part, Solution};
+use crate::{d1, d2, d3, d4, d5, d6, d7};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Timing {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+pub struct Day {
+    pub number: u8,
+    pub title: &'static str,
+    solve: fn(&str, Part) -> Result<(String, Timing)>,
+    examples: [&'static str; 2],
+}
+
+impl Day {
+    fn of<S: Solution>() -> Day {
+        return Day {
+            number: S::DAY,
+            title: S::TITLE,
+            solve: solve::<S>,
+            examples: [S::EXAMPLE, S::EXAMPLE_P2],
+        };
+    }
+
+    pub fn solve(&self, input: &str, part: Part) -> Result<(String, Timing)> {
+        return (self.solve)(input, part);
+    }
+
+    pub fn example(&self, part: Part) -> &'static str {
+        return match part {
+            Part::One => self.examples[0],
+            Part::Two => self.examples[1],
+        };
+    }
+}
+
+fn solve<S: Solution>(input: &str, part: Part) -> Result<(String, Timing)> {
+    let start = Instant::now();
+    let parsed = S::parse(input)?;
+    let parsed_at = Instant::now();
+    let answer = match part {
+        Part::One => S::part1(&parsed)?,
+        Part::Two => S::part2(&parsed)?,
+    };
+    let timing = Timing {
+        parse: parsed_at - start,
+        solve: parsed_at.elapsed(),
+    };
+    return Ok((answer.to_string(), timing));
+}
+
+// every implemented day, in calendar order
+pub fn days() -> Vec<Day> {
+    return vec![
+        Day::of::<d1::Day1>(),
+        Day::of::<d2::Day2>(),
+        Day::of::<d3::Day3>(),
+        Day::of::<d4::Day4>(),
+        Day::of::<d5::Day5>(),
+        Day::of::<d6::Day6>(),
+        Day::of::<d7::Day7>(),
+    ];
+}
+
+pub fn find(number: u8) -> Option<Day> {
+    return days().into_iter().find(|day| day.number == number);
+}
@@ -0,0 +1,245 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt::Display;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::{fs, io, panic};
+
+use crate::answers::Answers;
+use crate::color;
+use crate::error::{Error, Result};
+use crate::info;
+use crate::registry::{Day, Timing};
+use crate::solution::Part;
+
+pub enum Source {
+    Example,
+    File(PathBuf),
+    Stdin,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Source::Example => write!(f, "example"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "stdin"),
+        };
+    }
+}
+
+// resources/dN/input.txt, relative to the working directory like the rest of the crate
+pub fn input_path(day: u8) -> PathBuf {
+    return Path::new("resources")
+        .join(format!("d{}", day))
+        .join("input.txt");
+}
+
+// read a file or stdin source; examples are per part and come from the day itself
+pub fn read_source(source: &Source) -> Result<Option<String>> {
+    return match source {
+        Source::Example => Ok(None),
+        Source::File(path) => {
+            let input = fs::read_to_string(path)
+                .map_err(|err| Error::Io(path.display().to_string(), err))?;
+            info!("read {} bytes from {}", input.len(), path.display());
+            Ok(Some(input))
+        }
+        Source::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| Error::Io("stdin".to_string(), err))?;
+            info!("read {} bytes from stdin", input.len());
+            Ok(Some(input))
+        }
+    };
+}
+
+// the text of a file or stdin source, or `example` for Source::Example
+pub fn read_text(source: &Source, example: &str) -> Result<String> {
+    return Ok(read_source(source)?.unwrap_or_else(|| example.to_string()));
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        return msg.to_string();
+    }
+    if let Some(msg) = payload.downcast_ref::<String>() {
+        return msg.clone();
+    }
+    return "unknown panic".to_string();
+}
+
+thread_local! {
+    static SOLVING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs a panic hook that stays quiet while `run` is solving, since those panics are
+/// reported as errors; any other panic prints as usual. Call once at startup.
+pub fn quiet_solver_panics() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !SOLVING.with(Cell::get) {
+            default_hook(info);
+        }
+    }));
+}
+
+pub fn run(day: &Day, part: Part, input: &str) -> Result<(String, Timing)> {
+    // a broken day should be reported like any other failure, not take the whole run down
+    SOLVING.with(|solving| solving.set(true));
+    let res = panic::catch_unwind(|| day.solve(input, part));
+    SOLVING.with(|solving| solving.set(false));
+
+    return res.unwrap_or_else(|payload| Err(Error::Panic(panic_message(payload))));
+}
+
+fn is_not_found(err: &Error) -> bool {
+    return matches!(err, Error::Io(_, err) if err.kind() == io::ErrorKind::NotFound);
+}
+
+// run the selected parts against `source`, or against the example and the real input when no
+// source is given; returns false if anything failed
+pub fn run_day(day: &Day, parts: &[Part], source: Option<&Source>) -> bool {
+    let default_sources = [Source::Example, Source::File(input_path(day.number))];
+    let sources: Vec<&Source> = match source {
+        Some(source) => vec![source],
+        None => default_sources.iter().collect(),
+    };
+    let source_is_default = |s: &Source| source.is_none() && matches!(s, Source::File(_));
+
+    let mut ok = true;
+    for source in sources {
+        let input = match read_source(source) {
+            Ok(input) => input,
+            // not every day has a puzzle input checked in, only an explicit --input must exist
+            Err(err) if source_is_default(source) && is_not_found(&err) => {
+                eprintln!("Day {} ({}): skipped, no input file", day.number, source);
+                continue;
+            }
+            Err(err) => {
+                eprintln!("Day {} ({}): error: {}", day.number, source, err);
+                ok = false;
+                continue;
+            }
+        };
+        for &part in parts {
+            let input = input.as_deref().unwrap_or(day.example(part));
+            let label = format!("Day {} part {} ({})", day.number, part, source);
+            match run(day, part, input) {
+                Ok((answer, timing)) => println!(
+                    "{}: {}  (parse {:.2?}, solve {:.2?})",
+                    label, answer, timing.parse, timing.solve
+                ),
+                Err(err) => {
+                    eprintln!("{}: error: {}", label, err);
+                    ok = false;
+                }
+            }
+        }
+    }
+    return ok;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    // no expected answer recorded
+    Skip,
+    Error,
+}
+
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    pub source: &'static str,
+    pub expected: Option<String>,
+    pub actual: std::result::Result<String, String>,
+}
+
+impl Check {
+    pub fn verdict(&self) -> Verdict {
+        return match (&self.expected, &self.actual) {
+            (_, Err(_)) => Verdict::Error,
+            (None, Ok(_)) => Verdict::Skip,
+            (Some(expected), Ok(actual)) if expected == actual => Verdict::Pass,
+            (Some(_), Ok(_)) => Verdict::Fail,
+        };
+    }
+}
+
+// solve both parts on the example and the real input and compare against answers.toml
+pub fn verify_day(day: &Day) -> Vec<Check> {
+    let answers = Answers::load(day.number);
+    let real_input = read_source(&Source::File(input_path(day.number)));
+    let missing_input = matches!(&real_input, Err(err) if is_not_found(err));
+
+    let mut checks = vec![];
+    for source in ["example", "input"] {
+        for part in [Part::One, Part::Two] {
+            let expected = match &answers {
+                Ok(answers) => answers
+                    .as_ref()
+                    .and_then(|answers| answers.expected(source, part))
+                    .map(|s| s.to_string()),
+                Err(_) => None,
+            };
+            if source == "input" && missing_input && expected.is_none() {
+                continue;
+            }
+            let input = match (source, &real_input) {
+                ("example", _) => Ok(day.example(part).to_string()),
+                (_, Ok(input)) => Ok(input.clone().unwrap_or_default()),
+                (_, Err(err)) => Err(err.to_string()),
+            };
+            let actual = match (&answers, input) {
+                (Err(err), _) => Err(err.to_string()),
+                (_, Err(err)) => Err(err),
+                (_, Ok(input)) => run(day, part, &input)
+                    .map(|(answer, _)| answer)
+                    .map_err(|err| err.to_string()),
+            };
+            checks.push(Check {
+                day: day.number,
+                part,
+                source,
+                expected,
+                actual,
+            });
+        }
+    }
+    return checks;
+}
+
+pub fn print_checks(checks: &[Check]) {
+    println!(
+        "{:>3}  {:>4}  {:<7}  {:>16}  {:>16}  Result",
+        "Day", "Part", "Source", "Expected", "Actual"
+    );
+    for check in checks {
+        let verdict = match check.verdict() {
+            Verdict::Pass => color::paint("PASS", color::GREEN),
+            Verdict::Fail => color::paint("FAIL", color::RED),
+            Verdict::Skip => color::paint("SKIP", color::YELLOW),
+            Verdict::Error => color::paint("ERROR", color::RED),
+        };
+        let actual = match &check.actual {
+            Ok(actual) => actual.as_str(),
+            Err(_) => "-",
+        };
+        println!(
+            "{:>3}  {:>4}  {:<7}  {:>16}  {:>16}  {}",
+            check.day,
+            check.part,
+            check.source,
+            check.expected.as_deref().unwrap_or("-"),
+            actual,
+            verdict
+        );
+        if let Err(err) = &check.actual {
+            println!("     {}", err);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// A piece of a line found by one of the scanners below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    /// Byte offset of the token in the scanned string.
+    pub offset: usize,
+    /// 0-based character index of the token, which is what columns are counted in.
+    pub index: usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn end(&self) -> usize {
+        return self.offset + self.text.len();
+    }
+
+    /// The same token in a longer string, where the scanned one starts `offset` bytes and
+    /// `index` characters in.
+    pub fn offset_by(self, offset: usize, index: usize) -> Token<'a> {
+        return Token {
+            text: self.text,
+            offset: self.offset + offset,
+            index: self.index + index,
+        };
+    }
+
+    /// Parses the token, reporting `line` and the token's column on failure.
+    pub fn parse<T: FromStr>(&self, line: usize) -> Result<T> {
+        return self.text.parse::<T>().map_err(|_| {
+            let digits = self.text.strip_prefix('-').unwrap_or(self.text);
+            let message = if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                format!("number {} is too large", self.text)
+            } else {
+                format!("expected a number, found '{}'", self.text)
+            };
+            Error::parse_at(line, self.index + 1, message)
+        });
+    }
+}
+
+/// Iterates over the maximal runs of characters accepted by `accepts`, optionally allowing a
+/// leading `-` when it is directly followed by an accepted character.
+pub struct Runs<'a, F> {
+    s: &'a str,
+    offset: usize,
+    index: usize,
+    // columns are byte offsets on ASCII text, which saves counting characters
+    ascii: bool,
+    signed: bool,
+    accepts: F,
+}
+
+impl<F> Runs<'_, F> {
+    fn width(&self, s: &str) -> usize {
+        if self.ascii {
+            return s.len();
+        }
+        return s.chars().count();
+    }
+}
+
+impl<'a, F: Fn(char) -> bool> Iterator for Runs<'a, F> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        while self.offset < self.s.len() {
+            // jump straight to the next character that could begin a token
+            let rest = &self.s[self.offset..];
+            let skip = rest
+                .find(|c: char| (self.accepts)(c) || (self.signed && c == '-'))
+                .unwrap_or(rest.len());
+            self.index += self.width(&rest[..skip]);
+            self.offset += skip;
+            let rest = &rest[skip..];
+            if rest.is_empty() {
+                break;
+            }
+            let prefix = usize::from(self.signed && rest.starts_with('-'));
+            let body = rest[prefix..]
+                .find(|c: char| !(self.accepts)(c))
+                .unwrap_or(rest.len() - prefix);
+            if body == 0 {
+                // a '-' that does not start a number
+                self.offset += 1;
+                self.index += 1;
+                continue;
+            }
+            let text = &rest[..prefix + body];
+            let token = Token {
+                text,
+                offset: self.offset,
+                index: self.index,
+            };
+            self.offset += text.len();
+            self.index += self.width(text);
+            return Some(token);
+        }
+        return None;
+    }
+}
+
+fn runs<F: Fn(char) -> bool>(s: &str, signed: bool, accepts: F) -> Runs<'_, F> {
+    return Runs {
+        s,
+        offset: 0,
+        index: 0,
+        ascii: s.is_ascii(),
+        signed,
+        accepts,
+    };
+}
+
+/// Runs of ASCII digits: `"a12-3"` gives `12` and `3`.
+pub fn numbers(s: &str) -> impl Iterator<Item = Token<'_>> {
+    return runs(s, false, |c| c.is_ascii_digit());
+}
+
+/// Runs of ASCII digits with an optional leading `-`: `"a12-3"` gives `12` and `-3`.
+pub fn signed_numbers(s: &str) -> impl Iterator<Item = Token<'_>> {
+    return runs(s, true, |c| c.is_ascii_digit());
+}
+
+/// Runs of letters: `"seed-to-soil map:"` gives `seed`, `to`, `soil` and `map`.
+pub fn words(s: &str) -> impl Iterator<Item = Token<'_>> {
+    return runs(s, false, char::is_alphabetic);
+}
+
+/// Whitespace-separated fields, like `str::split_whitespace` but with positions.
+pub fn fields(s: &str) -> impl Iterator<Item = Token<'_>> {
+    return runs(s, false, |c| !c.is_whitespace());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(tokens: impl Iterator<Item = Token<'a>>) -> Vec<&'a str> {
+        return tokens.map(|token| token.text).collect();
+    }
+
+    #[test]
+    fn numbers_and_signed_numbers() {
+        assert_eq!(texts(numbers("a12-3..45")), vec!["12", "3", "45"]);
+        assert_eq!(
+            texts(signed_numbers("a12-3..-x-45")),
+            vec!["12", "-3", "-45"]
+        );
+        assert_eq!(texts(numbers("")), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn words_and_fields() {
+        assert_eq!(
+            texts(words("seed-to-soil map:")),
+            vec!["seed", "to", "soil", "map"]
+        );
+        assert_eq!(
+            texts(fields("  Card 1:  41 |  6 ")),
+            vec!["Card", "1:", "41", "|", "6"]
+        );
+    }
+
+    #[test]
+    fn tokens_know_where_they_are() {
+        let tokens: Vec<Token> = numbers("é.12..7").collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    text: "12",
+                    offset: 3,
+                    index: 2
+                },
+                Token {
+                    text: "7",
+                    offset: 7,
+                    index: 6
+                },
+            ]
+        );
+        assert_eq!(tokens[0].end(), 5);
+    }
+
+    #[test]
+    fn parse_errors_carry_the_column() {
+        let token = fields("1 x2").nth(1).unwrap();
+        assert_eq!(
+            token.parse::<i64>(4).unwrap_err().to_string(),
+            "line 4, column 3: expected a number, found 'x2'"
+        );
+        let token = numbers(" 99999999999").next().unwrap();
+        assert_eq!(
+            token.parse::<i32>(1).unwrap_err().to_string(),
+            "line 1, column 2: number 99999999999 is too large"
+        );
+    }
+}
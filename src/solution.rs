@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        };
+    }
+}
+
+/// One Advent of Code day: parse the puzzle input once, then answer both parts from it.
+pub trait Solution {
+    const DAY: u8;
+    const TITLE: &'static str;
+    /// Example from the puzzle text; `EXAMPLE_P2` only differs when part two ships its own.
+    const EXAMPLE: &'static str;
+    const EXAMPLE_P2: &'static str = Self::EXAMPLE;
+
+    /// Parsed form of the puzzle input, allowed to borrow from the raw text.
+    type Input<'a>;
+    type Answer: Display;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+    /// Errors when an answer cannot be computed, such as a count too large for `Answer`.
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer>;
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer>;
+}